@@ -0,0 +1,288 @@
+use std::fmt::Write;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::multi_thread_processor::{multi_thread_sum_of_squares, simd, sum_of_squares};
+use crate::parallel_matrix::Matrix;
+use crate::shared_memory_concurrency::concurrent_map::MapBenchmarkResult;
+
+// Ways of computing the sum of squares that the harness compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    SingleThread,
+    MultiThread,
+    SimdMultiThread,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [
+        Strategy::SingleThread,
+        Strategy::MultiThread,
+        Strategy::SimdMultiThread,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::SingleThread => "single_thread",
+            Strategy::MultiThread => "multi_thread",
+            Strategy::SimdMultiThread => "simd_multi_thread",
+        }
+    }
+
+    fn run(self, data: &Arc<Vec<u64>>, num_threads: usize) -> u64 {
+        match self {
+            Strategy::SingleThread => sum_of_squares(data),
+            Strategy::MultiThread => {
+                multi_thread_sum_of_squares(Arc::clone(data), num_threads)
+                    .expect("benchmark worker panicked")
+                    .value
+            }
+            Strategy::SimdMultiThread => simd::parallel_sum_of_squares(data, num_threads),
+        }
+    }
+}
+
+// What to run: every strategy for every input size, multi-threaded strategies for every thread count
+#[derive(Debug, Clone)]
+pub struct BenchmarkConfig {
+    pub warmup_runs: usize,
+    pub runs: usize,
+    pub thread_counts: Vec<usize>,
+    pub input_sizes: Vec<usize>,
+    pub strategies: Vec<Strategy>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        BenchmarkConfig {
+            warmup_runs: 2,
+            runs: 10,
+            thread_counts: vec![1, 2, 4, 8, 12],
+            input_sizes: vec![1_000_000, 10_000_000],
+            strategies: Strategy::ALL.to_vec(),
+        }
+    }
+}
+
+// Summary statistics of the measured runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    // Compute statistics from raw samples; panics if `samples` is empty
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        // Nearest-rank percentile
+        let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance =
+            secs.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / secs.len() as f64;
+
+        Stats {
+            min: sorted[0],
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+// Measurements for one (strategy, input size, thread count) combination
+#[derive(Debug, Clone)]
+pub struct BenchmarkResult {
+    pub strategy: Strategy,
+    pub input_size: usize,
+    pub threads: usize,
+    pub stats: Stats,
+    // Single-threaded median divided by this median, for the same input size
+    pub speedup: f64,
+}
+
+// Time `run` after the configured warmup runs
+fn measure<R>(config: &BenchmarkConfig, run: impl Fn() -> R) -> Stats {
+    for _ in 0..config.warmup_runs {
+        black_box(run());
+    }
+    let samples: Vec<Duration> = (0..config.runs.max(1))
+        .map(|_| {
+            let start_time = Instant::now();
+            black_box(run());
+            start_time.elapsed()
+        })
+        .collect();
+    Stats::from_samples(&samples)
+}
+
+// Run the whole sweep. The single-threaded baseline is always measured so speedups can be computed.
+pub fn run_benchmarks(config: &BenchmarkConfig) -> Vec<BenchmarkResult> {
+    let mut results = vec![];
+
+    for &input_size in &config.input_sizes {
+        let data = Arc::new(vec![1u64; input_size]);
+        let baseline = measure(config, || Strategy::SingleThread.run(black_box(&data), 1));
+        let speedup = |stats: &Stats| {
+            baseline.median.as_secs_f64() / stats.median.as_secs_f64().max(f64::MIN_POSITIVE)
+        };
+
+        for &strategy in &config.strategies {
+            if strategy == Strategy::SingleThread {
+                results.push(BenchmarkResult {
+                    strategy,
+                    input_size,
+                    threads: 1,
+                    stats: baseline,
+                    speedup: 1.0,
+                });
+                continue;
+            }
+            for &threads in &config.thread_counts {
+                let stats = measure(config, || strategy.run(black_box(&data), threads));
+                results.push(BenchmarkResult {
+                    strategy,
+                    input_size,
+                    threads,
+                    speedup: speedup(&stats),
+                    stats,
+                });
+            }
+        }
+    }
+
+    results
+}
+
+// Naive single-threaded against blocked parallel multiply of one square matrix size
+#[derive(Debug, Clone)]
+pub struct MatrixBenchmarkResult {
+    pub size: usize,
+    pub threads: usize,
+    pub naive: Stats,
+    pub parallel: Stats,
+    pub speedup: f64, // Naive median divided by parallel median
+}
+
+// Multiply `size` x `size` matrices with both implementations for every configured thread count
+pub fn run_matrix_benchmarks(
+    sizes: &[usize],
+    config: &BenchmarkConfig,
+) -> Vec<MatrixBenchmarkResult> {
+    let mut results = vec![];
+
+    for &size in sizes {
+        let a = Matrix::from_fn(size, size, |r, c| ((r * 7 + c * 3) % 11) as f64);
+        let b = Matrix::from_fn(size, size, |r, c| ((r + c * 5) % 13) as f64);
+        let naive = measure(config, || black_box(&a).multiply_naive(&b));
+
+        for &threads in &config.thread_counts {
+            let parallel = measure(config, || black_box(&a).multiply(&b, threads));
+            results.push(MatrixBenchmarkResult {
+                size,
+                threads,
+                naive,
+                parallel,
+                speedup: naive.median.as_secs_f64()
+                    / parallel.median.as_secs_f64().max(f64::MIN_POSITIVE),
+            });
+        }
+    }
+
+    results
+}
+
+// Render matrix results as an aligned plain-text table
+pub fn render_matrix_table(results: &[MatrixBenchmarkResult]) -> String {
+    let mut table = format!(
+        "{:>6} {:>7} {:>14} {:>14} {:>8}\n",
+        "size", "threads", "naive median", "blocked median", "speedup"
+    );
+    for r in results {
+        let _ = writeln!(
+            table,
+            "{:>6} {:>7} {:>14} {:>14} {:>7.2}x",
+            r.size,
+            r.threads,
+            format!("{:.2?}", r.naive.median),
+            format!("{:.2?}", r.parallel.median),
+            r.speedup
+        );
+    }
+    table
+}
+
+// Render results as an aligned plain-text table
+pub fn render_table(results: &[BenchmarkResult]) -> String {
+    let mut table = format!(
+        "{:<18} {:>12} {:>7} {:>12} {:>12} {:>12} {:>12} {:>8}\n",
+        "strategy", "size", "threads", "min", "median", "p95", "stddev", "speedup"
+    );
+    for r in results {
+        let _ = writeln!(
+            table,
+            "{:<18} {:>12} {:>7} {:>12} {:>12} {:>12} {:>12} {:>7.2}x",
+            r.strategy.name(),
+            r.input_size,
+            r.threads,
+            format!("{:.2?}", r.stats.min),
+            format!("{:.2?}", r.stats.median),
+            format!("{:.2?}", r.stats.p95),
+            format!("{:.2?}", r.stats.stddev),
+            r.speedup
+        );
+    }
+    table
+}
+
+// Render results as a JSON array, durations in nanoseconds
+pub fn render_json(results: &[BenchmarkResult]) -> String {
+    let entries: Vec<String> = results
+        .iter()
+        .map(|r| {
+            format!(
+                "{{\"strategy\":\"{}\",\"input_size\":{},\"threads\":{},\"min_ns\":{},\"median_ns\":{},\"p95_ns\":{},\"mean_ns\":{},\"stddev_ns\":{},\"speedup\":{:.4}}}",
+                r.strategy.name(),
+                r.input_size,
+                r.threads,
+                r.stats.min.as_nanos(),
+                r.stats.median.as_nanos(),
+                r.stats.p95.as_nanos(),
+                r.stats.mean.as_nanos(),
+                r.stats.stddev.as_nanos(),
+                r.speedup
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+// Render concurrent map results as an aligned plain-text table
+pub fn render_map_table(results: &[MapBenchmarkResult]) -> String {
+    let mut table = format!(
+        "{:<8} {:>6} {:>10} {:>12} {:>14}\n",
+        "backend", "reads", "operations", "elapsed", "ops/s"
+    );
+    for r in results {
+        let _ = writeln!(
+            table,
+            "{:<8} {:>5.0}% {:>10} {:>12} {:>14.0}",
+            r.backend,
+            r.read_ratio * 100.0,
+            r.operations,
+            format!("{:.2?}", r.elapsed),
+            r.operations_per_sec()
+        );
+    }
+    table
+}
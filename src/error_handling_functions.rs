@@ -1,9 +1,12 @@
 use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
 use std::net::TcpStream;
 use std::num::ParseIntError;
 use std::str::FromStr;
 use std::panic;
+use std::thread;
+
+use crate::shared_memory_concurrency::deadlock::{DeadlockError, DebugMutex};
 
 // File operation: Errors may occur when opening, reading, or writing files, such as file not found or insufficient permissions.
 pub fn file_handling() -> io::Result<()> {
@@ -34,14 +37,40 @@ pub fn data_parsing(data: &str) -> Result<i32, ParseIntError> {
 
 // System call: Errors may occur when interacting with the operating system, such as failed system API calls or error codes returned.
 pub fn system_call() -> io::Result<()> {
-    let _result = std::fs::remove_file("nonexistent.txt")?;
+    std::fs::remove_file("nonexistent.txt")?;
     Ok(())
 }
 
 // Concurrent operation: Concurrent errors may occur in multithreading or asynchronous programming, such as race conditions or deadlocks.
-pub fn concurrent_operation() -> Result<(), &'static str> {
-    // This is just a simple example and does not involve concurrent operations
-    Err("Concurrency error occurred")
+// Returns the number of ledger entries and the balance seen by the audit.
+pub fn concurrent_operation() -> Result<(usize, i32), DeadlockError> {
+    // Two threads take the same pair of locks in opposite order. They run one after the other so nothing
+    // actually hangs, but the debug locks still see the inverted order and report it as a potential deadlock.
+    let accounts = DebugMutex::new("accounts", 100);
+    let ledger = DebugMutex::new("ledger", Vec::<i32>::new());
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name(String::from("deposit"))
+            .spawn_scoped(scope, || {
+                let mut balance = accounts.lock()?;
+                *balance += 10;
+                ledger.lock()?.push(10);
+                Ok(())
+            })
+            .expect("failed to spawn thread")
+            .join()
+            .unwrap()?;
+        thread::Builder::new()
+            .name(String::from("audit"))
+            .spawn_scoped(scope, || {
+                let entries = ledger.lock()?;
+                let balance = accounts.lock()?;
+                Ok((entries.len(), *balance))
+            })
+            .expect("failed to spawn thread")
+            .join()
+            .unwrap()
+    })
 }
 
 // Third-party library call: Errors may occur when calling third-party libraries, such as error codes returned by library functions.
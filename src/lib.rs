@@ -0,0 +1,9 @@
+pub mod async_io_computation;
+pub mod benchmark;
+pub mod error_handling_functions;
+pub mod macros_generics_traits_closures;
+pub mod multi_thread_processor;
+pub mod network_handler;
+pub mod parallel_matrix;
+pub mod pipeline;
+pub mod shared_memory_concurrency;
@@ -1,11 +1,12 @@
-mod async_io_computation;
-mod error_handling_functions;
-mod multi_thread_processor;
-mod network_handler;
-mod shared_memory_concurrency;
-mod macros_generics_traits_closures;
-use error_handling_functions::*;
-use macros_generics_traits_closures::{add, DisplayMessage, Message, calculate_product};
+use be_rust_master::error_handling_functions::*;
+use be_rust_master::macros_generics_traits_closures::{
+    add, calculate_product, DisplayMessage, Message,
+};
+use be_rust_master::multi_thread_processor::offload::{OffloadError, Offloader};
+use be_rust_master::{
+    async_io_computation, benchmark, multi_thread_processor, print_message,
+    shared_memory_concurrency,
+};
 
 fn ownership_example() {
     let s1 = String::from("Crypto"); // Create a new String s1 containing "Crypto"
@@ -14,7 +15,7 @@ fn ownership_example() {
                         // println!("{}", s1); // Attempting to use s1 here would result in a compile-time error
 }
 
-fn calculate_length(s: &String) -> usize {
+fn calculate_length(s: &str) -> usize {
     s.len() // Return the length of the String s
 }
 
@@ -85,7 +86,7 @@ fn first_word(s: &str) -> &str {
             return &s[0..i]; // Return the substring up to the space
         }
     }
-    &s[..] // If no space is found, return the entire string
+    s // If no space is found, return the entire string
 }
 
 fn lifetime_elision_example() {
@@ -96,6 +97,29 @@ fn lifetime_elision_example() {
 
 #[tokio::main]
 async fn main() {
+    // Run the benchmark sweep instead of the demos: `cargo run --release -- --bench [--json]`
+    if std::env::args().any(|arg| arg == "--bench") {
+        let config = benchmark::BenchmarkConfig::default();
+        let results = benchmark::run_benchmarks(&config);
+        if std::env::args().any(|arg| arg == "--json") {
+            println!("{}", benchmark::render_json(&results));
+        } else {
+            print!("{}", benchmark::render_table(&results));
+            let matrix_results = benchmark::run_matrix_benchmarks(&[128, 256], &config);
+            print!("\n{}", benchmark::render_matrix_table(&matrix_results));
+            let map_results = shared_memory_concurrency::concurrent_map::compare_maps(
+                &[0.5, 0.9, 0.99],
+                &Default::default(),
+            );
+            print!("\n{}", benchmark::render_map_table(&map_results));
+        }
+        return;
+    }
+
+    // Lock contention stats are opt-in: `cargo run -- --lock-stats`
+    let lock_stats = std::env::args().any(|arg| arg == "--lock-stats");
+    shared_memory_concurrency::instrumented_mutex::set_instrumentation_enabled(lock_stats);
+
     ownership_example(); // Demonstrate ownership concepts
     immutable_borrowing_example(); // Demonstrate immutable borrowing
     mutable_borrowing_example(); // Demonstrate mutable borrowing
@@ -156,17 +180,65 @@ async fn main() {
 
     println!("\nStarting single-threaded computation...");
     let single_thread_result = multi_thread_processor::single_thread_computation();
-    println!("Single-threaded result: {}", single_thread_result);
+    println!(
+        "Single-threaded computation took: \x1b[31m{:?}\x1b[0m",
+        single_thread_result.elapsed
+    );
+    println!("Single-threaded result: {}", single_thread_result.value);
 
     println!("\nStarting multi-threaded computation...");
-    let multi_thread_result = multi_thread_processor::multi_thread_computation();
-    println!("Multi-threaded result: {}\n", multi_thread_result);
+    // Run the job off the runtime thread instead of blocking it
+    let offloader = Offloader::new(1);
+    let multi_thread_job = offloader
+        .run(multi_thread_processor::multi_thread_computation)
+        .await
+        .and_then(|result| result.map_err(OffloadError::from));
+    match multi_thread_job {
+        Ok(multi_thread_result) => {
+            println!(
+                "Multi-threaded computation took: \x1b[31m{:?}\x1b[0m ({} threads, slowest worker {:?})",
+                multi_thread_result.elapsed,
+                multi_thread_result.threads,
+                multi_thread_result.thread_timings.iter().max().unwrap()
+            );
+            println!("Multi-threaded result: {}", multi_thread_result.value);
+        }
+        Err(err) => eprintln!("Multi-threaded computation error: {}", err),
+    }
+
+    println!("\nStarting SIMD multi-threaded computation...");
+    let simd_result = multi_thread_processor::simd_multi_thread_computation();
+    println!(
+        "SIMD multi-threaded computation ({:?}) took: \x1b[31m{:?}\x1b[0m",
+        multi_thread_processor::simd::detected_level(),
+        simd_result.elapsed
+    );
+    println!("SIMD multi-threaded result: {}\n", simd_result.value);
 
     // network_handler::start_network_handler();
     // loop{};
 
     shared_memory_concurrency::demonstrate_shared_memory_concurrency();
 
+    if lock_stats {
+        println!("\nLock contention so far:");
+        for report in shared_memory_concurrency::instrumented_mutex::contention_report() {
+            print!("{}", report);
+        }
+    }
+
+    println!("\nComparing shared counter backends under contention...");
+    for run in shared_memory_concurrency::counter::compare_counters(8, 100_000) {
+        println!(
+            "{:>12}: final value {} in \x1b[31m{:?}\x1b[0m ({:.0} increments/s)",
+            run.backend,
+            run.final_value,
+            run.elapsed,
+            run.increments_per_sec()
+        );
+    }
+    println!();
+
     async_io_computation::async_example().await;
 
     // Call the macro
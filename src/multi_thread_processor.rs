@@ -1,68 +1,228 @@
-use std::sync::{Arc, Mutex};
+pub mod affinity;
+pub mod aggregations;
+pub mod control;
+pub mod float_reduce;
+pub mod isolation;
+pub mod mmap_input;
+pub mod offload;
+pub mod parallel_primitives;
+pub mod rng;
+pub mod simd;
+pub mod streaming;
+
+use std::ops::Range;
+use std::sync::{Arc, PoisonError};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use crate::shared_memory_concurrency::instrumented_mutex::InstrumentedMutex;
+use control::{Cancelled, JobControl, JobError, Progress};
+use isolation::WorkerPanic;
 
 const NUM_ELEMENTS: usize = 100_000_000; // 100 million elements
 const NUM_THREADS: usize = 12; // Number of threads to use
 
-// Single-threaded computation
-pub fn single_thread_computation() -> u64 {
-    let data = vec![1u64; NUM_ELEMENTS]; // Initialize an array with 100 million elements, each element is 1
+// Split `len` elements into `parts` contiguous ranges, the last range takes the remainder
+pub fn chunk_ranges(len: usize, parts: usize) -> Vec<Range<usize>> {
+    let parts = parts.max(1);
+    let chunk_size = len / parts;
+    (0..parts)
+        .map(|i| {
+            let start = i * chunk_size;
+            let end = if i == parts - 1 {
+                len
+            } else {
+                start + chunk_size
+            };
+            start..end
+        })
+        .collect()
+}
 
-    let start_time = Instant::now();
-    let sum_of_squares: u64 = data.iter().map(|&x| x * x).sum();
-    let duration = start_time.elapsed();
-
-    // println!("Single-threaded computation took: {:?}", duration);
-    println!(
-        "Single-threaded computation took: \x1b[31m{:?}\x1b[0m",
-        duration
-    );
-    sum_of_squares
+// Sum of squares on the calling thread
+pub fn sum_of_squares(data: &[u64]) -> u64 {
+    data.iter().map(|&x| x * x).sum()
 }
 
-// Multi-threaded computation
-pub fn multi_thread_computation() -> u64 {
-    let data = Arc::new(vec![1u64; NUM_ELEMENTS]); // Wrap the array in an Arc to share it between threads
+// Outcome of a computation: the value plus timings, presentation is left to the caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputationResult {
+    pub value: u64,
+    pub elapsed: Duration,
+    pub thread_timings: Vec<Duration>, // Time each worker spent on its chunk, in chunk order
+    pub threads: usize,
+}
+
+// Sum of squares split across `num_threads` spawned threads, partial sums are added under a Mutex.
+// A panicking worker is reported as a `WorkerPanic` instead of panicking the caller.
+pub fn multi_thread_sum_of_squares(
+    data: Arc<Vec<u64>>,
+    num_threads: usize,
+) -> Result<ComputationResult, WorkerPanic> {
+    multi_thread_sum_of_squares_with_retries(data, num_threads, 0)
+}
+
+// Like `multi_thread_sum_of_squares`, retrying a panicking chunk up to `max_retries` times
+pub fn multi_thread_sum_of_squares_with_retries(
+    data: Arc<Vec<u64>>,
+    num_threads: usize,
+    max_retries: usize,
+) -> Result<ComputationResult, WorkerPanic> {
+    // Nothing else holds the default token, so the job cannot be cancelled
+    run_sum_of_squares(data, num_threads, max_retries, &JobControl::default()).map_err(|err| {
+        match err {
+            JobError::Panicked(err) => err,
+            JobError::Cancelled(_) => unreachable!("job without a shared token was cancelled"),
+        }
+    })
+}
 
+// Worker loop behind every multi-threaded sum of squares. Each chunk is processed in
+// `control.sub_chunk_size` pieces, checking the token and reporting progress between pieces.
+pub(crate) fn run_sum_of_squares(
+    data: Arc<Vec<u64>>,
+    num_threads: usize,
+    max_retries: usize,
+    control: &JobControl,
+) -> Result<ComputationResult, JobError> {
     let start_time = Instant::now();
     let mut handles = vec![];
 
-    let chunk_size = NUM_ELEMENTS / NUM_THREADS;
+    let result = Arc::new(InstrumentedMutex::new(
+        "multi_thread_processor::result",
+        0u64,
+    ));
+    let sub_chunk_size = control.sub_chunk_size.max(1);
 
-    let result = Arc::new(Mutex::new(0u64));
-
-    for i in 0..NUM_THREADS {
+    for (chunk, range) in chunk_ranges(data.len(), num_threads)
+        .into_iter()
+        .enumerate()
+    {
         let data = Arc::clone(&data);
         let result = Arc::clone(&result);
+        let cancellation = control.cancellation.clone();
+        let progress = control.progress.clone();
+        let worker_range = range.clone();
 
         let handle = thread::spawn(move || {
-            let start = i * chunk_size;
-            let end = if i == NUM_THREADS - 1 {
-                NUM_ELEMENTS
-            } else {
-                start + chunk_size
-            };
-
-            let sum: u64 = data[start..end].iter().map(|&x| x * x).sum();
-            let mut result = result.lock().unwrap();
+            let thread_start = Instant::now();
+            let chunk_data = &data[worker_range.clone()];
+            // A retry starts the chunk over, progress included
+            let (sum, processed, completed) =
+                isolation::catch_chunk(chunk, worker_range, max_retries, || {
+                    let mut sum = 0u64;
+                    let mut processed = 0;
+                    for sub_chunk in chunk_data.chunks(sub_chunk_size) {
+                        if cancellation.is_cancelled() {
+                            return (sum, processed, false);
+                        }
+                        sum += sum_of_squares(sub_chunk);
+                        processed += sub_chunk.len();
+                        if let Some(progress) = &progress {
+                            progress(Progress {
+                                worker: chunk,
+                                processed,
+                                total: chunk_data.len(),
+                            });
+                        }
+                    }
+                    (sum, processed, true)
+                })?;
+            // The lock is never held across a panic, but recover the value rather than propagate poisoning
+            let mut result = result.lock().unwrap_or_else(PoisonError::into_inner);
             *result += sum;
+            Ok((thread_start.elapsed(), processed, completed))
         });
 
-        handles.push(handle);
+        handles.push((chunk, range, handle));
     }
 
-    for handle in handles {
-        handle.join().unwrap();
+    let mut outcomes: Vec<(Duration, usize, bool)> = vec![];
+    let mut failure = None;
+    for (chunk, range, handle) in handles {
+        // Join every worker before returning, keeping the failure of the lowest chunk
+        let outcome = handle.join().unwrap_or_else(|payload| {
+            Err(WorkerPanic {
+                chunk,
+                range,
+                message: isolation::panic_message(payload.as_ref()),
+                attempts: 1,
+            })
+        });
+        match outcome {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(err) => {
+                failure.get_or_insert(err);
+            }
+        }
+    }
+    // A panic is reported even if the job was also cancelled
+    if let Some(err) = failure {
+        return Err(JobError::Panicked(err));
+    }
+    if outcomes.iter().any(|&(_, _, completed)| !completed) {
+        return Err(JobError::Cancelled(Cancelled {
+            processed: outcomes.iter().map(|&(_, processed, _)| processed).sum(),
+        }));
     }
 
-    let duration = start_time.elapsed();
-    // println!("Multi-threaded computation took: {:?}", duration);
-    println!(
-        "Multi-threaded computation took: \x1b[31m{:?}\x1b[0m",
-        duration
-    );
+    let value = *result.lock().unwrap_or_else(PoisonError::into_inner);
+    Ok(ComputationResult {
+        value,
+        elapsed: start_time.elapsed(),
+        thread_timings: outcomes.iter().map(|&(timing, _, _)| timing).collect(),
+        threads: outcomes.len(),
+    })
+}
 
-    let result = *result.lock().unwrap();
-    result
+// Single-threaded computation
+pub fn single_thread_computation() -> ComputationResult {
+    let data = vec![1u64; NUM_ELEMENTS]; // Initialize an array with 100 million elements, each element is 1
+
+    let start_time = Instant::now();
+    let value = sum_of_squares(&data);
+    let elapsed = start_time.elapsed();
+
+    ComputationResult {
+        value,
+        elapsed,
+        thread_timings: vec![elapsed],
+        threads: 1,
+    }
+}
+
+// Multi-threaded computation
+pub fn multi_thread_computation() -> Result<ComputationResult, WorkerPanic> {
+    let data = Arc::new(vec![1u64; NUM_ELEMENTS]); // Wrap the array in an Arc to share it between threads
+    multi_thread_sum_of_squares(data, NUM_THREADS)
+}
+
+// Multi-threaded computation with explicit SIMD kernels in each worker
+pub fn simd_multi_thread_computation() -> ComputationResult {
+    let data = vec![1u64; NUM_ELEMENTS];
+
+    let start_time = Instant::now();
+    let partials: Vec<(u64, Duration)> = thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(data.len(), NUM_THREADS)
+            .into_iter()
+            .map(|range| {
+                let data = &data[range];
+                scope.spawn(move || {
+                    let thread_start = Instant::now();
+                    (simd::sum_of_squares(data), thread_start.elapsed())
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    ComputationResult {
+        value: partials.iter().map(|&(sum, _)| sum).sum(),
+        elapsed: start_time.elapsed(),
+        thread_timings: partials.iter().map(|&(_, timing)| timing).collect(),
+        threads: partials.len(),
+    }
 }
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::Instant;
+
+use super::{chunk_ranges, sum_of_squares, ComputationResult};
+
+// Where worker threads should run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pinning {
+    // Leave placement to the scheduler
+    None,
+    // Worker `i` is pinned to `cores[i % cores.len()]`
+    Cores(Vec<usize>),
+    // Workers are dealt round-robin across NUMA nodes, each pinned to the next free core of its node
+    SpreadNodes,
+}
+
+// Placement a worker asked for and the placement it actually got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerPlacement {
+    pub worker: usize,
+    pub requested_core: Option<usize>,
+    pub pinned: bool,                 // Whether the affinity call succeeded
+    pub pin_error: Option<String>,    // Why the affinity call failed
+    pub observed_core: Option<usize>, // Core the worker was running on when it finished
+    pub numa_node: Option<usize>,     // Node of `observed_core`
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacementReport {
+    pub workers: Vec<WorkerPlacement>,
+}
+
+impl fmt::Display for PlacementReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show =
+            |value: Option<usize>| value.map_or_else(|| String::from("-"), |v| v.to_string());
+        writeln!(
+            f,
+            "{:>6} {:>9} {:>6} {:>8} {:>5}  error",
+            "worker", "requested", "pinned", "observed", "node"
+        )?;
+        for w in &self.workers {
+            writeln!(
+                f,
+                "{:>6} {:>9} {:>6} {:>8} {:>5}  {}",
+                w.worker,
+                show(w.requested_core),
+                w.pinned,
+                show(w.observed_core),
+                show(w.numa_node),
+                w.pin_error.as_deref().unwrap_or("-")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Parse a sysfs CPU list such as "0-3,8,10-11"
+pub fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = vec![];
+    for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => cpus.extend(first.parse::<usize>().ok()?..=last.parse().ok()?),
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+// CPUs of each NUMA node the calling thread may run on, indexed by node.
+// Without NUMA information every allowed CPU is on node 0.
+pub fn numa_topology() -> Vec<Vec<usize>> {
+    let allowed = allowed_cores().ok();
+    let mut nodes = vec![];
+    if cfg!(target_os = "linux") {
+        for node in 0.. {
+            let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+            match std::fs::read_to_string(path)
+                .ok()
+                .and_then(|list| parse_cpu_list(&list))
+            {
+                Some(cpus) => nodes.push(cpus),
+                None => break,
+            }
+        }
+    }
+    match allowed {
+        // Nodes left empty by the mask keep their index
+        Some(allowed) if !nodes.is_empty() => {
+            for cpus in &mut nodes {
+                cpus.retain(|cpu| allowed.contains(cpu));
+            }
+        }
+        Some(allowed) => nodes.push(allowed),
+        None if nodes.is_empty() => {
+            let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+            nodes.push((0..cpus).collect());
+        }
+        None => {}
+    }
+    nodes
+}
+
+// NUMA node that `core` belongs to
+pub fn node_of_core(topology: &[Vec<usize>], core: usize) -> Option<usize> {
+    topology.iter().position(|cpus| cpus.contains(&core))
+}
+
+// Cores the calling thread may run on; threads it spawns inherit the same mask
+#[cfg(target_os = "linux")]
+pub fn allowed_cores() -> io::Result<Vec<usize>> {
+    // SAFETY: `cpu_set_t` is plain data, the kernel writes at most the size passed in,
+    // CPU_ISSET is only asked about indices below CPU_SETSIZE, and pid 0 refers to the calling thread
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|&core| libc::CPU_ISSET(core, &set))
+            .collect())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn allowed_cores() -> io::Result<Vec<usize>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "affinity masks are only supported on Linux",
+    ))
+}
+
+// Pin the calling thread to a single core
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(core: usize) -> io::Result<()> {
+    // SAFETY: `cpu_set_t` is plain data, CPU_SET is bounds-checked against CPU_SETSIZE below,
+    // and pid 0 refers to the calling thread
+    unsafe {
+        if core >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "core index out of range",
+            ));
+        }
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_core: usize) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "thread pinning is only supported on Linux",
+    ))
+}
+
+// Core the calling thread is running on right now
+#[cfg(target_os = "linux")]
+pub fn current_core() -> Option<usize> {
+    // SAFETY: sched_getcpu has no preconditions
+    let core = unsafe { libc::sched_getcpu() };
+    usize::try_from(core).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn current_core() -> Option<usize> {
+    None
+}
+
+// Core each worker should be pinned to under `pinning`
+fn planned_cores(
+    pinning: &Pinning,
+    num_workers: usize,
+    topology: &[Vec<usize>],
+) -> Vec<Option<usize>> {
+    match pinning {
+        Pinning::None => vec![None; num_workers],
+        Pinning::Cores(cores) if cores.is_empty() => vec![None; num_workers],
+        Pinning::Cores(cores) => (0..num_workers)
+            .map(|i| Some(cores[i % cores.len()]))
+            .collect(),
+        Pinning::SpreadNodes => {
+            let nodes: Vec<&Vec<usize>> = topology.iter().filter(|cpus| !cpus.is_empty()).collect();
+            if nodes.is_empty() {
+                return vec![None; num_workers];
+            }
+            (0..num_workers)
+                .map(|i| {
+                    let cpus = nodes[i % nodes.len()];
+                    Some(cpus[(i / nodes.len()) % cpus.len()])
+                })
+                .collect()
+        }
+    }
+}
+
+// Run `work(worker)` on `num_workers` threads placed according to `pinning`.
+// Anything `work` allocates and first touches is placed on the worker's local NUMA node by the
+// kernel's first-touch policy, so per-worker data should be allocated inside `work`.
+pub fn run_pinned<R: Send>(
+    num_workers: usize,
+    pinning: &Pinning,
+    work: impl Fn(usize) -> R + Sync,
+) -> (Vec<R>, PlacementReport) {
+    let topology = numa_topology();
+    let plan = planned_cores(pinning, num_workers.max(1), &topology);
+    let (work, topology) = (&work, &topology);
+
+    let outcomes: Vec<(R, WorkerPlacement)> = thread::scope(|scope| {
+        let handles: Vec<_> = plan
+            .into_iter()
+            .enumerate()
+            .map(|(worker, requested_core)| {
+                scope.spawn(move || {
+                    let pin_error = requested_core
+                        .and_then(|core| pin_current_thread(core).err())
+                        .map(|err| err.to_string());
+                    let value = work(worker);
+                    let observed_core = current_core();
+                    let placement = WorkerPlacement {
+                        worker,
+                        requested_core,
+                        pinned: requested_core.is_some() && pin_error.is_none(),
+                        pin_error,
+                        observed_core,
+                        numa_node: observed_core.and_then(|core| node_of_core(topology, core)),
+                    };
+                    (value, placement)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    let (values, workers) = outcomes.into_iter().unzip();
+    (values, PlacementReport { workers })
+}
+
+// Sum of squares of `len` generated elements where each pinned worker allocates and fills its own
+// chunk, so the chunk lives on the worker's NUMA node
+pub fn numa_sum_of_squares(
+    len: usize,
+    num_threads: usize,
+    pinning: &Pinning,
+    element: impl Fn(usize) -> u64 + Sync,
+) -> (ComputationResult, PlacementReport) {
+    let start_time = Instant::now();
+    let ranges = chunk_ranges(len, num_threads);
+    let (partials, report) = run_pinned(ranges.len(), pinning, |worker| {
+        let chunk: Vec<u64> = ranges[worker].clone().map(&element).collect();
+        let thread_start = Instant::now();
+        (sum_of_squares(&chunk), thread_start.elapsed())
+    });
+
+    let result = ComputationResult {
+        value: partials.iter().map(|&(sum, _)| sum).sum(),
+        elapsed: start_time.elapsed(),
+        thread_timings: partials.iter().map(|&(_, timing)| timing).collect(),
+        threads: partials.len(),
+    };
+    (result, report)
+}
@@ -0,0 +1,362 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::thread;
+
+use super::chunk_ranges;
+
+// Run `fold` over each thread's chunk and merge the per-thread results in chunk order
+fn map_merge<T, A>(
+    data: &[T],
+    num_threads: usize,
+    fold: impl Fn(&[T]) -> A + Sync,
+    merge: impl Fn(A, A) -> A,
+) -> Option<A>
+where
+    T: Sync,
+    A: Send,
+{
+    let fold = &fold;
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(data.len(), num_threads)
+            .into_iter()
+            .map(|range| {
+                let chunk = &data[range];
+                scope.spawn(move || fold(chunk))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .reduce(merge)
+    })
+}
+
+// Counts per bin. Bin `i` covers `[edges[i], edges[i + 1])`, the last bin also includes its upper edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram<T> {
+    pub edges: Vec<T>,
+    pub counts: Vec<u64>,
+    pub below: u64,     // Values smaller than the first edge
+    pub above: u64,     // Values larger than the last edge
+    pub unordered: u64, // Values that compare with nothing, such as NaN
+}
+
+impl<T> Histogram<T> {
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.below + self.above + self.unordered
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistogramError {
+    TooFewEdges,
+    EdgesNotIncreasing { index: usize }, // `edges[index]` is not greater than the edge before it
+}
+
+impl fmt::Display for HistogramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistogramError::TooFewEdges => write!(f, "a histogram needs at least two bin edges"),
+            HistogramError::EdgesNotIncreasing { index } => {
+                write!(
+                    f,
+                    "bin edge {} is not greater than the previous edge",
+                    index
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistogramError {}
+
+// Histogram with user-supplied bin edges, each thread counting its chunk into its own bins
+pub fn parallel_histogram<T>(
+    data: &[T],
+    edges: &[T],
+    num_threads: usize,
+) -> Result<Histogram<T>, HistogramError>
+where
+    T: PartialOrd + Copy + Send + Sync,
+{
+    if edges.len() < 2 {
+        return Err(HistogramError::TooFewEdges);
+    }
+    if let Some(index) =
+        (1..edges.len()).find(|&i| edges[i - 1].partial_cmp(&edges[i]) != Some(Ordering::Less))
+    {
+        return Err(HistogramError::EdgesNotIncreasing { index });
+    }
+
+    let (first, last) = (edges[0], edges[edges.len() - 1]);
+    let empty = || Histogram {
+        edges: edges.to_vec(),
+        counts: vec![0; edges.len() - 1],
+        below: 0,
+        above: 0,
+        unordered: 0,
+    };
+
+    let count_chunk = |chunk: &[T]| {
+        let mut histogram = empty();
+        for &x in chunk {
+            if x < first {
+                histogram.below += 1;
+            } else if x > last {
+                histogram.above += 1;
+            } else if x >= first && x <= last {
+                // Number of edges <= x, minus one, is the bin; clamp so the last edge falls in the last bin
+                let bin = edges.partition_point(|&edge| edge <= x) - 1;
+                histogram.counts[bin.min(edges.len() - 2)] += 1;
+            } else {
+                histogram.unordered += 1;
+            }
+        }
+        histogram
+    };
+
+    let merge = |mut a: Histogram<T>, b: Histogram<T>| {
+        for (count, other) in a.counts.iter_mut().zip(b.counts) {
+            *count += other;
+        }
+        a.below += b.below;
+        a.above += b.above;
+        a.unordered += b.unordered;
+        a
+    };
+
+    Ok(map_merge(data, num_threads, count_chunk, merge).unwrap_or_else(empty))
+}
+
+// Exact frequency of every distinct value, counted in per-thread hash maps merged at the end
+pub fn parallel_frequencies<T>(data: &[T], num_threads: usize) -> HashMap<T, usize>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    let count_chunk = |chunk: &[T]| {
+        let mut counts = HashMap::new();
+        for value in chunk {
+            *counts.entry(value.clone()).or_insert(0) += 1;
+        }
+        counts
+    };
+    // Fold the smaller map into the larger one
+    let merge = |a: HashMap<T, usize>, b: HashMap<T, usize>| {
+        if a.len() >= b.len() {
+            merge_counts(a, b)
+        } else {
+            merge_counts(b, a)
+        }
+    };
+    map_merge(data, num_threads, count_chunk, merge).unwrap_or_default()
+}
+
+fn merge_counts<T: Hash + Eq>(
+    mut into: HashMap<T, usize>,
+    from: HashMap<T, usize>,
+) -> HashMap<T, usize> {
+    for (value, count) in from {
+        *into.entry(value).or_insert(0) += count;
+    }
+    into
+}
+
+// Estimated frequency of a value. The true count lies in `count - error ..= count`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopKEntry<T> {
+    pub value: T,
+    pub count: usize,
+    pub error: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Counter<T> {
+    value: T,
+    count: usize,
+    error: usize,
+}
+
+// Space-Saving summary: tracks at most `capacity` values. Every value occurring more than
+// n / capacity times is guaranteed to be tracked.
+// Counters form a binary min-heap on count, so the eviction candidate is always the root and
+// every insert costs O(log capacity).
+#[derive(Debug, Clone)]
+pub struct SpaceSaving<T> {
+    capacity: usize,
+    heap: Vec<Counter<T>>,
+    positions: HashMap<T, usize>, // value -> index of its counter in `heap`
+}
+
+impl<T: Hash + Eq + Clone> SpaceSaving<T> {
+    pub fn new(capacity: usize) -> Self {
+        SpaceSaving {
+            capacity: capacity.max(1),
+            heap: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    // Build a summary from at most `capacity` counters
+    fn from_counters(capacity: usize, counters: Vec<Counter<T>>) -> Self {
+        let mut summary = SpaceSaving {
+            capacity,
+            positions: counters
+                .iter()
+                .enumerate()
+                .map(|(index, counter)| (counter.value.clone(), index))
+                .collect(),
+            heap: counters,
+        };
+        for index in (0..summary.heap.len() / 2).rev() {
+            summary.sift_down(index);
+        }
+        summary
+    }
+
+    // Smallest tracked count once the summary is full; untracked values occurred at most this often
+    fn floor(&self) -> usize {
+        if self.heap.len() < self.capacity {
+            0
+        } else {
+            self.heap.first().map_or(0, |counter| counter.count)
+        }
+    }
+
+    fn counter(&self, value: &T) -> Option<&Counter<T>> {
+        self.positions.get(value).map(|&index| &self.heap[index])
+    }
+
+    pub fn insert(&mut self, value: &T) {
+        if let Some(&index) = self.positions.get(value) {
+            self.heap[index].count += 1;
+            self.sift_down(index);
+            return;
+        }
+        if self.heap.len() < self.capacity {
+            self.positions.insert(value.clone(), self.heap.len());
+            self.heap.push(Counter {
+                value: value.clone(),
+                count: 1,
+                error: 0,
+            });
+            self.sift_up(self.heap.len() - 1);
+            return;
+        }
+        // Replace the value with the smallest count; the newcomer inherits that count as its error
+        let min_count = self.heap[0].count;
+        self.positions.remove(&self.heap[0].value);
+        self.positions.insert(value.clone(), 0);
+        self.heap[0] = Counter {
+            value: value.clone(),
+            count: min_count + 1,
+            error: min_count,
+        };
+        self.sift_down(0);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.positions.get_mut(&self.heap[a].value).unwrap() = a;
+        *self.positions.get_mut(&self.heap[b].value).unwrap() = b;
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.heap[parent].count <= self.heap[index].count {
+                break;
+            }
+            self.swap(parent, index);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let smallest = [2 * index + 1, 2 * index + 2]
+                .into_iter()
+                .filter(|&child| child < self.heap.len())
+                .fold(index, |smallest, child| {
+                    if self.heap[child].count < self.heap[smallest].count {
+                        child
+                    } else {
+                        smallest
+                    }
+                });
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+
+    // Combine two summaries: a value missing from one side may have occurred up to that side's floor
+    pub fn merge(self, other: Self) -> Self {
+        let (floor_a, floor_b) = (self.floor(), other.floor());
+        let mut merged: HashMap<T, (usize, usize)> = HashMap::new();
+        for counter in &self.heap {
+            let (other_count, other_error) = other
+                .counter(&counter.value)
+                .map_or((floor_b, floor_b), |c| (c.count, c.error));
+            merged.insert(
+                counter.value.clone(),
+                (counter.count + other_count, counter.error + other_error),
+            );
+        }
+        for counter in &other.heap {
+            merged
+                .entry(counter.value.clone())
+                .or_insert((counter.count + floor_a, counter.error + floor_a));
+        }
+
+        let capacity = self.capacity.max(other.capacity);
+        let mut counters: Vec<Counter<T>> = merged
+            .into_iter()
+            .map(|(value, (count, error))| Counter {
+                value,
+                count,
+                error,
+            })
+            .collect();
+        counters.sort_by_key(|counter| Reverse(counter.count));
+        counters.truncate(capacity);
+        Self::from_counters(capacity, counters)
+    }
+
+    // The `k` values with the highest estimated counts, highest first
+    pub fn top(&self, k: usize) -> Vec<TopKEntry<T>> {
+        let mut entries: Vec<TopKEntry<T>> = self
+            .heap
+            .iter()
+            .map(|counter| TopKEntry {
+                value: counter.value.clone(),
+                count: counter.count,
+                error: counter.error,
+            })
+            .collect();
+        entries.sort_by(|a, b| b.count.cmp(&a.count).then(a.error.cmp(&b.error)));
+        entries.truncate(k);
+        entries
+    }
+}
+
+// Approximate `k` most frequent values using per-thread Space-Saving summaries
+pub fn parallel_top_k<T>(data: &[T], k: usize, num_threads: usize) -> Vec<TopKEntry<T>>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+{
+    let capacity = (k * 8).max(64);
+    let summarize = |chunk: &[T]| {
+        let mut summary = SpaceSaving::new(capacity);
+        for value in chunk {
+            summary.insert(value);
+        }
+        summary
+    };
+    map_merge(data, num_threads, summarize, SpaceSaving::merge)
+        .map(|summary| summary.top(k))
+        .unwrap_or_default()
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::isolation::WorkerPanic;
+use super::{run_sum_of_squares, ComputationResult};
+
+// Shared flag that asks a running job to stop; clones observe the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// Progress of one worker, reported after each sub-chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub worker: usize,
+    pub processed: usize, // Elements of this worker's chunk processed so far
+    pub total: usize,     // Elements in this worker's chunk
+}
+
+// Called from the worker threads; forward to a channel to observe progress from another thread
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+// How a job can be observed and stopped
+#[derive(Clone)]
+pub struct JobControl {
+    pub cancellation: CancellationToken,
+    pub progress: Option<ProgressCallback>,
+    pub sub_chunk_size: usize, // Elements processed between cancellation checks and progress reports
+}
+
+impl Default for JobControl {
+    fn default() -> Self {
+        JobControl {
+            cancellation: CancellationToken::new(),
+            progress: None,
+            sub_chunk_size: 1 << 20,
+        }
+    }
+}
+
+// Returned when a job stopped because its token was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled {
+    pub processed: usize, // Elements processed by all workers before they stopped
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "job cancelled after {} elements", self.processed)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+// Why a controlled job produced no result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobError {
+    Cancelled(Cancelled),
+    Panicked(WorkerPanic),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::Cancelled(err) => write!(f, "{}", err),
+            JobError::Panicked(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for JobError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JobError::Cancelled(err) => Some(err),
+            JobError::Panicked(err) => Some(err),
+        }
+    }
+}
+
+// `multi_thread_sum_of_squares_with_retries` that checks the token between sub-chunks and reports progress
+pub fn controlled_sum_of_squares(
+    data: Arc<Vec<u64>>,
+    num_threads: usize,
+    max_retries: usize,
+    control: &JobControl,
+) -> Result<ComputationResult, JobError> {
+    run_sum_of_squares(data, num_threads, max_retries, control)
+}
@@ -0,0 +1,102 @@
+use std::ops::Range;
+use std::thread;
+
+use super::chunk_ranges;
+
+// Elements summed per block. Blocks are fixed independently of the thread count, so the
+// reduction tree (and therefore every rounding step) is the same no matter how many threads run.
+const BLOCK_SIZE: usize = 4096;
+
+// Neumaier's variant of Kahan summation: carries the rounding error of each addition
+pub fn kahan_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+    for x in values {
+        let t = sum + x;
+        if sum.abs() >= x.abs() {
+            compensation += (sum - t) + x;
+        } else {
+            compensation += (x - t) + sum;
+        }
+        sum = t;
+    }
+    sum + compensation
+}
+
+// Recursive pairwise summation, error grows with log(n) instead of n
+pub fn pairwise_sum(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        1 => values[0],
+        n => {
+            let (left, right) = values.split_at(n / 2);
+            pairwise_sum(left) + pairwise_sum(right)
+        }
+    }
+}
+
+// Evaluate `block` on every fixed-size block of `0..len` across the threads, keeping block order
+fn block_partials(
+    len: usize,
+    num_threads: usize,
+    block: impl Fn(Range<usize>) -> f64 + Sync,
+) -> Vec<f64> {
+    let num_blocks = len.div_ceil(BLOCK_SIZE);
+    let block = &block;
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(num_blocks, num_threads)
+            .into_iter()
+            .filter(|blocks| !blocks.is_empty())
+            .map(|blocks| {
+                scope.spawn(move || {
+                    blocks
+                        .map(|b| block(b * BLOCK_SIZE..((b + 1) * BLOCK_SIZE).min(len)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+// Deterministic parallel sum: Kahan within blocks, pairwise across blocks
+pub fn parallel_sum(data: &[f64], num_threads: usize) -> f64 {
+    let partials = block_partials(data.len(), num_threads, |r| {
+        kahan_sum(data[r].iter().copied())
+    });
+    pairwise_sum(&partials)
+}
+
+// Arithmetic mean, `None` for an empty slice
+pub fn parallel_mean(data: &[f64], num_threads: usize) -> Option<f64> {
+    if data.is_empty() {
+        return None;
+    }
+    Some(parallel_sum(data, num_threads) / data.len() as f64)
+}
+
+// Population variance computed in two passes (mean, then squared deviations) to avoid cancellation
+pub fn parallel_variance(data: &[f64], num_threads: usize) -> Option<f64> {
+    let mean = parallel_mean(data, num_threads)?;
+    let partials = block_partials(data.len(), num_threads, |r| {
+        kahan_sum(data[r].iter().map(|&x| (x - mean) * (x - mean)))
+    });
+    Some(pairwise_sum(&partials) / data.len() as f64)
+}
+
+// Deterministic parallel dot product of two equally long slices; panics if the lengths differ
+pub fn parallel_dot(a: &[f64], b: &[f64], num_threads: usize) -> f64 {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "dot product of slices with different lengths"
+    );
+    let partials = block_partials(a.len(), num_threads, |r| {
+        kahan_sum(a[r.clone()].iter().zip(&b[r]).map(|(&x, &y)| x * y))
+    });
+    pairwise_sum(&partials)
+}
@@ -0,0 +1,101 @@
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::chunk_ranges;
+
+// A worker panicked on its chunk (on every attempt, if retries were allowed)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerPanic {
+    pub chunk: usize,
+    pub range: Range<usize>,
+    pub message: String,
+    pub attempts: usize,
+}
+
+impl fmt::Display for WorkerPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "worker for chunk {} ({:?}) panicked after {} attempt(s): {}",
+            self.chunk, self.range, self.attempts, self.message
+        )
+    }
+}
+
+impl std::error::Error for WorkerPanic {}
+
+// Extract the message from a panic payload (`panic!` produces either `&str` or `String`)
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("non-string panic payload")
+    }
+}
+
+// Run `work` for one chunk, catching panics and retrying up to `max_retries` more times
+pub fn catch_chunk<R>(
+    chunk: usize,
+    range: Range<usize>,
+    max_retries: usize,
+    work: impl Fn() -> R,
+) -> Result<R, WorkerPanic> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match panic::catch_unwind(AssertUnwindSafe(&work)) {
+            Ok(value) => return Ok(value),
+            Err(payload) if attempts > max_retries => {
+                return Err(WorkerPanic {
+                    chunk,
+                    range,
+                    message: panic_message(payload.as_ref()),
+                    attempts,
+                })
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+// Run `work(chunk_index, chunk)` on each chunk on its own thread with panics isolated per chunk.
+// Returns each chunk's result and timing in chunk order, or the failure of the lowest failing chunk.
+pub fn run_isolated<T, R>(
+    data: &[T],
+    num_threads: usize,
+    max_retries: usize,
+    work: impl Fn(usize, &[T]) -> R + Sync,
+) -> Result<Vec<(R, Duration)>, WorkerPanic>
+where
+    T: Sync,
+    R: Send,
+{
+    let work = &work;
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(data.len(), num_threads)
+            .into_iter()
+            .enumerate()
+            .map(|(chunk, range)| {
+                scope.spawn(move || {
+                    let thread_start = Instant::now();
+                    let value = catch_chunk(chunk, range.clone(), max_retries, || {
+                        work(chunk, &data[range.clone()])
+                    })?;
+                    Ok((value, thread_start.elapsed()))
+                })
+            })
+            .collect();
+
+        // Panics are caught inside the workers, so joining cannot fail
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::thread;
+
+use memmap2::Mmap;
+
+use super::{chunk_ranges, float_reduce, sum_of_squares};
+
+// Element types that can be viewed directly from a little-endian file: every bit pattern is valid
+// and there is no padding. Sealed so the zero-copy cast below stays sound.
+pub trait MappedElement: Copy + Send + Sync + sealed::Sealed {}
+
+impl MappedElement for u64 {}
+impl MappedElement for f64 {}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u64 {}
+    impl Sealed for f64 {}
+}
+
+// Reasons a file cannot be viewed as an array
+#[derive(Debug)]
+pub enum MmapError {
+    Io(io::Error),
+    // The file size is not a multiple of the element size
+    Truncated { file_len: u64, element_size: usize },
+    // The mapping does not start on an element boundary
+    Misaligned { address: usize, align: usize },
+    // The host is big-endian, so little-endian data cannot be used in place
+    UnsupportedEndianness,
+}
+
+impl fmt::Display for MmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MmapError::Io(err) => write!(f, "I/O error: {}", err),
+            MmapError::Truncated {
+                file_len,
+                element_size,
+            } => write!(
+                f,
+                "file is truncated: {} bytes is not a multiple of the {}-byte element size ({} trailing bytes)",
+                file_len,
+                element_size,
+                file_len % *element_size as u64
+            ),
+            MmapError::Misaligned { address, align } => write!(
+                f,
+                "mapping at {:#x} is not aligned to {} bytes",
+                address, align
+            ),
+            MmapError::UnsupportedEndianness => {
+                write!(f, "little-endian data cannot be mapped on a big-endian host")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MmapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MmapError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MmapError {
+    fn from(err: io::Error) -> Self {
+        MmapError::Io(err)
+    }
+}
+
+// A read-only memory-mapped file of little-endian `T` values
+pub struct MappedArray<T: MappedElement> {
+    mmap: Option<Mmap>, // `None` for an empty file, which cannot be mapped
+    len: usize,
+    _element: PhantomData<T>,
+}
+
+impl<T: MappedElement> MappedArray<T> {
+    // Map `path` and validate its length and alignment.
+    // The file must not be modified while it is mapped.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MmapError> {
+        if cfg!(target_endian = "big") {
+            return Err(MmapError::UnsupportedEndianness);
+        }
+
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let element_size = std::mem::size_of::<T>();
+        if !file_len.is_multiple_of(element_size as u64) {
+            return Err(MmapError::Truncated {
+                file_len,
+                element_size,
+            });
+        }
+        if file_len == 0 {
+            return Ok(MappedArray {
+                mmap: None,
+                len: 0,
+                _element: PhantomData,
+            });
+        }
+
+        // SAFETY: the mapping is read-only; callers are told not to modify the file while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        let address = mmap.as_ptr() as usize;
+        let align = std::mem::align_of::<T>();
+        if !address.is_multiple_of(align) {
+            return Err(MmapError::Misaligned { address, align });
+        }
+
+        Ok(MappedArray {
+            len: mmap.len() / element_size,
+            mmap: Some(mmap),
+            _element: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // View the mapping as a slice without copying
+    pub fn as_slice(&self) -> &[T] {
+        match &self.mmap {
+            // SAFETY: length and alignment were validated in `open`, the host is little-endian and
+            // `MappedElement` types accept any bit pattern
+            Some(mmap) => unsafe {
+                std::slice::from_raw_parts(mmap.as_ptr().cast::<T>(), self.len)
+            },
+            None => &[],
+        }
+    }
+
+    // Hand each worker thread its own sub-slice of the mapping and collect the results in chunk order
+    pub fn map_chunks<R: Send>(&self, num_threads: usize, f: impl Fn(&[T]) -> R + Sync) -> Vec<R> {
+        let data = self.as_slice();
+        let f = &f;
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk_ranges(data.len(), num_threads)
+                .into_iter()
+                .map(|range| {
+                    let chunk = &data[range];
+                    scope.spawn(move || f(chunk))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+}
+
+// Sum of squares of a file of little-endian `u64` values, read in place by `num_threads` workers.
+// Uses the same arithmetic as `sum_of_squares`, so overflow panics in debug builds like the other inputs.
+pub fn mapped_sum_of_squares(path: impl AsRef<Path>, num_threads: usize) -> Result<u64, MmapError> {
+    let array = MappedArray::<u64>::open(path)?;
+    Ok(array
+        .map_chunks(num_threads, sum_of_squares)
+        .into_iter()
+        .sum())
+}
+
+// Deterministic sum of a file of little-endian `f64` values, read in place by `num_threads` workers
+pub fn mapped_f64_sum(path: impl AsRef<Path>, num_threads: usize) -> Result<f64, MmapError> {
+    let array = MappedArray::<f64>::open(path)?;
+    Ok(float_reduce::parallel_sum(array.as_slice(), num_threads))
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+
+use tokio::sync::{oneshot, Semaphore};
+
+use super::isolation::{panic_message, WorkerPanic};
+use super::{multi_thread_sum_of_squares, ComputationResult};
+
+// Where offloaded jobs run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    // tokio's blocking thread pool (`spawn_blocking`)
+    BlockingPool,
+    // A dedicated OS thread per job, outside of tokio entirely
+    DedicatedThread,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OffloadError {
+    // The job panicked; carries the panic message
+    Panicked(String),
+    // The runtime shut down before the job could finish
+    Cancelled,
+    // A processor worker panicked inside the job
+    Worker(WorkerPanic),
+}
+
+impl fmt::Display for OffloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffloadError::Panicked(message) => write!(f, "offloaded job panicked: {}", message),
+            OffloadError::Cancelled => write!(f, "offloaded job was cancelled"),
+            OffloadError::Worker(err) => write!(f, "offloaded job failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for OffloadError {}
+
+impl From<WorkerPanic> for OffloadError {
+    fn from(err: WorkerPanic) -> Self {
+        OffloadError::Worker(err)
+    }
+}
+
+// Runs CPU-bound jobs off the async runtime threads. At most `max_concurrent_jobs` run at once,
+// further callers wait asynchronously, so concurrent requests do not oversubscribe the cores.
+#[derive(Debug, Clone)]
+pub struct Offloader {
+    permits: Arc<Semaphore>,
+    backend: Backend,
+}
+
+impl Offloader {
+    pub fn new(max_concurrent_jobs: usize) -> Self {
+        Self::with_backend(max_concurrent_jobs, Backend::BlockingPool)
+    }
+
+    pub fn with_backend(max_concurrent_jobs: usize, backend: Backend) -> Self {
+        Offloader {
+            permits: Arc::new(Semaphore::new(max_concurrent_jobs.max(1))),
+            backend,
+        }
+    }
+
+    // Jobs that could start right now without waiting
+    pub fn available_slots(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    // Run `job` on the backend and resolve to its result. The concurrency slot is held by the job
+    // itself, so it is released when the job finishes even if this future is dropped first.
+    pub async fn run<F, R>(&self, job: F) -> Result<R, OffloadError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        // The semaphore is never closed, so acquiring only waits
+        let permit = Arc::clone(&self.permits).acquire_owned().await.unwrap();
+
+        match self.backend {
+            Backend::BlockingPool => tokio::task::spawn_blocking(move || {
+                let result = job();
+                drop(permit);
+                result
+            })
+            .await
+            .map_err(|err| match err.try_into_panic() {
+                Ok(payload) => OffloadError::Panicked(panic_message(payload.as_ref())),
+                Err(_) => OffloadError::Cancelled,
+            }),
+            Backend::DedicatedThread => {
+                let (sender, receiver) = oneshot::channel();
+                thread::spawn(move || {
+                    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    // Give the slot back before the caller can observe the result
+                    drop(permit);
+                    // The receiver is gone if the caller stopped waiting; nothing to report then
+                    let _ = sender.send(outcome.map_err(|payload| panic_message(payload.as_ref())));
+                });
+                match receiver.await {
+                    Ok(outcome) => outcome.map_err(OffloadError::Panicked),
+                    Err(_) => Err(OffloadError::Cancelled),
+                }
+            }
+        }
+    }
+
+    // Async wrapper around `multi_thread_sum_of_squares`
+    pub async fn sum_of_squares(
+        &self,
+        data: Arc<Vec<u64>>,
+        num_threads: usize,
+    ) -> Result<ComputationResult, OffloadError> {
+        self.run(move || multi_thread_sum_of_squares(data, num_threads))
+            .await?
+            .map_err(OffloadError::from)
+    }
+}
@@ -0,0 +1,244 @@
+use std::ops::{Add, Range};
+use std::thread;
+
+use super::chunk_ranges;
+
+// Non-empty chunk ranges, still contiguous and in order
+fn work_ranges(len: usize, num_threads: usize) -> Vec<Range<usize>> {
+    chunk_ranges(len, num_threads)
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+// Split `data` into the disjoint mutable slices described by contiguous `ranges` starting at 0
+fn split_ranges_mut<'a, T>(mut data: &'a mut [T], ranges: &[Range<usize>]) -> Vec<&'a mut [T]> {
+    let mut offset = 0;
+    ranges
+        .iter()
+        .map(|range| {
+            let (head, tail) = std::mem::take(&mut data).split_at_mut(range.end - offset);
+            offset = range.end;
+            data = tail;
+            head
+        })
+        .collect()
+}
+
+// Stable merge of two sorted slices into `dst`, which must be exactly as long as both together
+fn merge_into<T: Ord + Clone>(left: &[T], right: &[T], dst: &mut [T]) {
+    let (mut i, mut j) = (0, 0);
+    for slot in dst.iter_mut() {
+        if j == right.len() || (i < left.len() && left[i] <= right[j]) {
+            *slot = left[i].clone();
+            i += 1;
+        } else {
+            *slot = right[j].clone();
+            j += 1;
+        }
+    }
+}
+
+// Stable sort: each thread sorts its chunk, then adjacent runs are merged pairwise in parallel
+pub fn parallel_merge_sort<T: Ord + Clone + Send + Sync>(data: &mut [T], num_threads: usize) {
+    let mut runs = work_ranges(data.len(), num_threads);
+
+    thread::scope(|scope| {
+        for chunk in split_ranges_mut(data, &runs) {
+            scope.spawn(move || chunk.sort());
+        }
+    });
+
+    if runs.len() <= 1 {
+        return;
+    }
+    // Passes alternate between `data` and `buffer`, so each element is copied once per pass
+    // and the result is copied back at most once at the end
+    let mut buffer = data.to_vec();
+    let mut sorted_in_data = true;
+    while runs.len() > 1 {
+        let merged: Vec<Range<usize>> = runs
+            .chunks(2)
+            .map(|pair| pair[0].start..pair[pair.len() - 1].end)
+            .collect();
+
+        let (src, dst): (&[T], &mut [T]) = if sorted_in_data {
+            (data, &mut buffer)
+        } else {
+            (&buffer, data)
+        };
+        thread::scope(|scope| {
+            // An unpaired last run is merged with nothing, which copies it across
+            for (pair, dst) in runs.chunks(2).zip(split_ranges_mut(dst, &merged)) {
+                let left = &src[pair[0].clone()];
+                let right = pair.get(1).map_or(&[][..], |range| &src[range.clone()]);
+                scope.spawn(move || merge_into(left, right, dst));
+            }
+        });
+
+        sorted_in_data = !sorted_in_data;
+        runs = merged;
+    }
+    if !sorted_in_data {
+        data.clone_from_slice(&buffer);
+    }
+}
+
+// Single-threaded reference for `parallel_merge_sort`
+pub fn sequential_merge_sort<T: Ord>(data: &mut [T]) {
+    data.sort();
+}
+
+// Binary search over sorted chunks. Each thread counts the elements of its chunk that are less than
+// `target`; the total is the lower bound. Returns `Ok` with the first matching index, or `Err` with
+// the insertion point, so the result does not depend on the thread count.
+pub fn parallel_binary_search<T: Ord + Sync>(
+    sorted: &[T],
+    target: &T,
+    num_threads: usize,
+) -> Result<usize, usize> {
+    let lower_bound: usize = thread::scope(|scope| {
+        let handles: Vec<_> = work_ranges(sorted.len(), num_threads)
+            .into_iter()
+            .map(|range| {
+                let chunk = &sorted[range];
+                scope.spawn(move || chunk.partition_point(|x| x < target))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .sum()
+    });
+    match sorted.get(lower_bound) {
+        Some(x) if x == target => Ok(lower_bound),
+        _ => Err(lower_bound),
+    }
+}
+
+// Single-threaded reference for `parallel_binary_search`
+pub fn sequential_binary_search<T: Ord>(sorted: &[T], target: &T) -> Result<usize, usize> {
+    let lower_bound = sorted.partition_point(|x| x < target);
+    match sorted.get(lower_bound) {
+        Some(x) if x == target => Ok(lower_bound),
+        _ => Err(lower_bound),
+    }
+}
+
+// Inclusive prefix sum in three phases: scan each chunk, scan the chunk totals, add the offsets
+pub fn parallel_prefix_sum<T>(data: &[T], num_threads: usize) -> Vec<T>
+where
+    T: Copy + Add<Output = T> + Send + Sync,
+{
+    let mut output = data.to_vec();
+    let ranges = work_ranges(data.len(), num_threads);
+
+    let totals: Vec<T> = thread::scope(|scope| {
+        let handles: Vec<_> = split_ranges_mut(&mut output, &ranges)
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    for i in 1..chunk.len() {
+                        chunk[i] = chunk[i - 1] + chunk[i];
+                    }
+                    chunk[chunk.len() - 1]
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    // offsets[i] is the sum of every chunk before chunk i; the first chunk needs no offset
+    let offsets: Vec<Option<T>> = totals
+        .iter()
+        .scan(None, |running: &mut Option<T>, &total| {
+            let offset = *running;
+            *running = Some(running.map_or(total, |sum| sum + total));
+            Some(offset)
+        })
+        .collect();
+
+    thread::scope(|scope| {
+        for (chunk, offset) in split_ranges_mut(&mut output, &ranges)
+            .into_iter()
+            .zip(offsets)
+        {
+            if let Some(offset) = offset {
+                scope.spawn(move || chunk.iter_mut().for_each(|x| *x = offset + *x));
+            }
+        }
+    });
+
+    output
+}
+
+// Single-threaded reference for `parallel_prefix_sum`
+pub fn sequential_prefix_sum<T: Copy + Add<Output = T>>(data: &[T]) -> Vec<T> {
+    let mut output = Vec::with_capacity(data.len());
+    for &x in data {
+        let next = output.last().map_or(x, |&last| last + x);
+        output.push(next);
+    }
+    output
+}
+
+// Order-preserving filter: each thread filters its chunk, the pieces are concatenated in chunk order
+pub fn parallel_filter<T>(
+    data: &[T],
+    num_threads: usize,
+    predicate: impl Fn(&T) -> bool + Sync,
+) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+{
+    parallel_partition(data, num_threads, predicate).0
+}
+
+// Single-threaded reference for `parallel_filter`
+pub fn sequential_filter<T: Clone>(data: &[T], predicate: impl Fn(&T) -> bool) -> Vec<T> {
+    data.iter().filter(|x| predicate(x)).cloned().collect()
+}
+
+// Stable partition into (matching, not matching), both in input order
+pub fn parallel_partition<T>(
+    data: &[T],
+    num_threads: usize,
+    predicate: impl Fn(&T) -> bool + Sync,
+) -> (Vec<T>, Vec<T>)
+where
+    T: Clone + Send + Sync,
+{
+    let predicate = &predicate;
+    let parts: Vec<(Vec<T>, Vec<T>)> = thread::scope(|scope| {
+        let handles: Vec<_> = work_ranges(data.len(), num_threads)
+            .into_iter()
+            .map(|range| {
+                let chunk = &data[range];
+                scope.spawn(move || sequential_partition(chunk, predicate))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    let mut matching = Vec::with_capacity(parts.iter().map(|p| p.0.len()).sum());
+    let mut rest = Vec::with_capacity(parts.iter().map(|p| p.1.len()).sum());
+    for (yes, no) in parts {
+        matching.extend(yes);
+        rest.extend(no);
+    }
+    (matching, rest)
+}
+
+// Single-threaded reference for `parallel_partition`
+pub fn sequential_partition<T: Clone>(
+    data: &[T],
+    predicate: impl Fn(&T) -> bool,
+) -> (Vec<T>, Vec<T>) {
+    data.iter().cloned().partition(|x| predicate(x))
+}
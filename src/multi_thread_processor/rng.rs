@@ -0,0 +1,149 @@
+use std::thread;
+
+use super::chunk_ranges;
+use super::float_reduce::{kahan_sum, pairwise_sum};
+
+// Samples drawn from one stream. Work is split into fixed blocks with one stream each, so the
+// numbers drawn do not depend on how many threads share the blocks.
+const SAMPLES_PER_STREAM: u64 = 1 << 16;
+
+// SplitMix64: used to expand seeds into generator state
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+// xoshiro256**: fast, seedable and splittable generator (not for cryptographic use)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        let mut seeder = SplitMix64::new(seed);
+        Xoshiro256 {
+            s: [
+                seeder.next_u64(),
+                seeder.next_u64(),
+                seeder.next_u64(),
+                seeder.next_u64(),
+            ],
+        }
+    }
+
+    // Independent generator for stream `index` of `seed`; the same pair always gives the same stream.
+    // Half of the state is expanded from the seed and half from the index mixed with it. A SplitMix64
+    // draw is a bijection of its seed, so distinct pairs always start from distinct states.
+    pub fn stream(seed: u64, index: u64) -> Self {
+        let mut from_seed = SplitMix64::new(seed);
+        let (s0, s1) = (from_seed.next_u64(), from_seed.next_u64());
+        let mut from_index = SplitMix64::new(s1 ^ index);
+        Xoshiro256 {
+            s: [s0, s1, from_index.next_u64(), from_index.next_u64()],
+        }
+    }
+
+    // Derive a child generator from this one, advancing this generator
+    pub fn split(&mut self) -> Self {
+        Self::seed_from_u64(self.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    // Uniform in [0, 1) with 53 random bits
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+// Mean of `samples` draws of `sample`, reproducible for a given seed whatever the thread count
+pub fn parallel_monte_carlo(
+    samples: u64,
+    seed: u64,
+    num_threads: usize,
+    sample: impl Fn(&mut Xoshiro256) -> f64 + Sync,
+) -> f64 {
+    if samples == 0 {
+        return f64::NAN;
+    }
+    let num_blocks = samples.div_ceil(SAMPLES_PER_STREAM) as usize;
+    let sample = &sample;
+
+    let partials: Vec<f64> = thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(num_blocks, num_threads)
+            .into_iter()
+            .filter(|blocks| !blocks.is_empty())
+            .map(|blocks| {
+                scope.spawn(move || {
+                    blocks
+                        .map(|block| {
+                            let block = block as u64;
+                            let count =
+                                SAMPLES_PER_STREAM.min(samples - block * SAMPLES_PER_STREAM);
+                            let mut rng = Xoshiro256::stream(seed, block);
+                            kahan_sum((0..count).map(|_| sample(&mut rng)))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    pairwise_sum(&partials) / samples as f64
+}
+
+// Estimate pi from the fraction of random points in the unit square that fall inside the quarter circle
+pub fn estimate_pi(samples: u64, seed: u64, num_threads: usize) -> f64 {
+    4.0 * parallel_monte_carlo(samples, seed, num_threads, |rng| {
+        let (x, y) = (rng.next_f64(), rng.next_f64());
+        if x * x + y * y < 1.0 {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+// Estimate the integral of `f` over [a, b] by uniform sampling
+pub fn estimate_integral(
+    f: impl Fn(f64) -> f64 + Sync,
+    a: f64,
+    b: f64,
+    samples: u64,
+    seed: u64,
+    num_threads: usize,
+) -> f64 {
+    (b - a)
+        * parallel_monte_carlo(samples, seed, num_threads, |rng| {
+            f(a + (b - a) * rng.next_f64())
+        })
+}
@@ -0,0 +1,666 @@
+use std::ops::Range;
+use std::thread;
+
+use super::chunk_ranges;
+
+// Number of independent accumulators per kernel, enough to fill a 256-bit register for every element type
+const LANES: usize = 8;
+
+// Instruction set the kernels are dispatched to at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimdLevel {
+    Scalar,
+    Sse41,
+    Avx2,
+}
+
+// Detect the best instruction set supported by the running CPU
+pub fn detected_level() -> SimdLevel {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return SimdLevel::Avx2;
+        }
+        if is_x86_feature_detected!("sse4.1") {
+            return SimdLevel::Sse41;
+        }
+    }
+    SimdLevel::Scalar
+}
+
+// Element types supported by the kernels. Integer arithmetic wraps on overflow so the lanes stay branch-free,
+// float min/max ignore NaN unless every element is NaN.
+pub trait SimdElement: Copy + Send + Sync {
+    const ZERO: Self;
+    fn lane_add(self, other: Self) -> Self;
+    fn lane_mul(self, other: Self) -> Self;
+    fn lane_min(self, other: Self) -> Self;
+    fn lane_max(self, other: Self) -> Self;
+
+    // Kernels for this type on the instruction set picked by `detected_level`
+    fn simd_sum(data: &[Self]) -> Self;
+    fn simd_sum_of_squares(data: &[Self]) -> Self;
+    fn simd_dot(a: &[Self], b: &[Self]) -> Self;
+    fn simd_min_max(data: &[Self]) -> Option<(Self, Self)>;
+}
+
+// Call the intrinsics kernel `$kernel` from `x86::<level>::$module` for the detected level,
+// or the portable `$fallback` when no supported instruction set is available
+macro_rules! dispatch {
+    ($module:ident, $kernel:ident, $fallback:ident($($arg:ident),*)) => {{
+        #[cfg(target_arch = "x86_64")]
+        match detected_level() {
+            // SAFETY: the running CPU supports the instruction set each module is compiled for
+            SimdLevel::Avx2 => return unsafe { x86::avx2::$module::$kernel($($arg),*) },
+            SimdLevel::Sse41 => return unsafe { x86::sse41::$module::$kernel($($arg),*) },
+            SimdLevel::Scalar => {}
+        }
+        $fallback($($arg),*)
+    }};
+}
+
+macro_rules! impl_kernels {
+    ($module:ident) => {
+        fn simd_sum(data: &[Self]) -> Self {
+            dispatch!($module, sum, sum_kernel(data))
+        }
+
+        fn simd_sum_of_squares(data: &[Self]) -> Self {
+            dispatch!($module, sum_of_squares, sum_of_squares_kernel(data))
+        }
+
+        fn simd_dot(a: &[Self], b: &[Self]) -> Self {
+            dispatch!($module, dot, dot_kernel(a, b))
+        }
+
+        fn simd_min_max(data: &[Self]) -> Option<(Self, Self)> {
+            dispatch!($module, min_max, min_max_kernel(data))
+        }
+    };
+}
+
+macro_rules! impl_simd_int {
+    ($($t:ty => $module:ident),*) => {
+        $(
+            impl SimdElement for $t {
+                const ZERO: Self = 0;
+
+                impl_kernels!($module);
+
+                #[inline(always)]
+                fn lane_add(self, other: Self) -> Self {
+                    self.wrapping_add(other)
+                }
+
+                #[inline(always)]
+                fn lane_mul(self, other: Self) -> Self {
+                    self.wrapping_mul(other)
+                }
+
+                #[inline(always)]
+                fn lane_min(self, other: Self) -> Self {
+                    if other < self { other } else { self }
+                }
+
+                #[inline(always)]
+                fn lane_max(self, other: Self) -> Self {
+                    if other > self { other } else { self }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_simd_float {
+    ($($t:ty => $module:ident),*) => {
+        $(
+            impl SimdElement for $t {
+                const ZERO: Self = 0.0;
+
+                impl_kernels!($module);
+
+                #[inline(always)]
+                fn lane_add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                #[inline(always)]
+                fn lane_mul(self, other: Self) -> Self {
+                    self * other
+                }
+
+                #[inline(always)]
+                fn lane_min(self, other: Self) -> Self {
+                    self.min(other)
+                }
+
+                #[inline(always)]
+                fn lane_max(self, other: Self) -> Self {
+                    self.max(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_simd_int!(u64 => uint64, i64 => int64);
+impl_simd_float!(f32 => float32, f64 => float64);
+
+// Portable fallback kernels for CPUs without AVX2/SSE4.1 and other architectures: independent
+// accumulators the compiler may or may not vectorise. The x86 kernels below use intrinsics directly.
+#[inline(always)]
+fn sum_kernel<T: SimdElement>(data: &[T]) -> T {
+    let mut acc = [T::ZERO; LANES];
+    let mut chunks = data.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        for (a, &x) in acc.iter_mut().zip(chunk) {
+            *a = a.lane_add(x);
+        }
+    }
+    let total = acc.iter().fold(T::ZERO, |a, &b| a.lane_add(b));
+    chunks.remainder().iter().fold(total, |a, &b| a.lane_add(b))
+}
+
+#[inline(always)]
+fn sum_of_squares_kernel<T: SimdElement>(data: &[T]) -> T {
+    let mut acc = [T::ZERO; LANES];
+    let mut chunks = data.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        for (a, &x) in acc.iter_mut().zip(chunk) {
+            *a = a.lane_add(x.lane_mul(x));
+        }
+    }
+    let total = acc.iter().fold(T::ZERO, |a, &b| a.lane_add(b));
+    chunks
+        .remainder()
+        .iter()
+        .fold(total, |a, &x| a.lane_add(x.lane_mul(x)))
+}
+
+#[inline(always)]
+fn dot_kernel<T: SimdElement>(a: &[T], b: &[T]) -> T {
+    let mut acc = [T::ZERO; LANES];
+    let mut chunks_a = a.chunks_exact(LANES);
+    let mut chunks_b = b.chunks_exact(LANES);
+    for (chunk_a, chunk_b) in (&mut chunks_a).zip(&mut chunks_b) {
+        for ((acc, &x), &y) in acc.iter_mut().zip(chunk_a).zip(chunk_b) {
+            *acc = acc.lane_add(x.lane_mul(y));
+        }
+    }
+    let total = acc.iter().fold(T::ZERO, |a, &b| a.lane_add(b));
+    chunks_a
+        .remainder()
+        .iter()
+        .zip(chunks_b.remainder())
+        .fold(total, |acc, (&x, &y)| acc.lane_add(x.lane_mul(y)))
+}
+
+#[inline(always)]
+fn min_max_kernel<T: SimdElement>(data: &[T]) -> Option<(T, T)> {
+    let first = *data.first()?;
+    let mut min = [first; LANES];
+    let mut max = [first; LANES];
+    let mut chunks = data.chunks_exact(LANES);
+    for chunk in &mut chunks {
+        for ((lo, hi), &x) in min.iter_mut().zip(max.iter_mut()).zip(chunk) {
+            *lo = lo.lane_min(x);
+            *hi = hi.lane_max(x);
+        }
+    }
+    let lo = min.iter().fold(first, |a, &b| a.lane_min(b));
+    let hi = max.iter().fold(first, |a, &b| a.lane_max(b));
+    Some(
+        chunks
+            .remainder()
+            .iter()
+            .fold((lo, hi), |(lo, hi), &x| (lo.lane_min(x), hi.lane_max(x))),
+    )
+}
+
+pub fn sum<T: SimdElement>(data: &[T]) -> T {
+    T::simd_sum(data)
+}
+
+pub fn sum_of_squares<T: SimdElement>(data: &[T]) -> T {
+    T::simd_sum_of_squares(data)
+}
+
+// Float min/max ignore NaN unless every element is NaN
+pub fn min_max<T: SimdElement>(data: &[T]) -> Option<(T, T)> {
+    T::simd_min_max(data)
+}
+
+fn dot_unchecked<T: SimdElement>(a: &[T], b: &[T]) -> T {
+    T::simd_dot(a, b)
+}
+
+// Dot product of two equally long slices; panics if the lengths differ
+pub fn dot<T: SimdElement>(a: &[T], b: &[T]) -> T {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "dot product of slices with different lengths"
+    );
+    dot_unchecked(a, b)
+}
+
+// Run `kernel` on each thread's chunk and fold the partial results; empty chunks are skipped
+fn parallel_reduce<R: Send>(
+    len: usize,
+    num_threads: usize,
+    kernel: impl Fn(Range<usize>) -> R + Sync,
+    combine: impl Fn(R, R) -> R,
+) -> Option<R> {
+    let kernel = &kernel;
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunk_ranges(len, num_threads)
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| scope.spawn(move || kernel(range)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .reduce(combine)
+    })
+}
+
+pub fn parallel_sum<T: SimdElement>(data: &[T], num_threads: usize) -> T {
+    parallel_reduce(data.len(), num_threads, |r| sum(&data[r]), T::lane_add).unwrap_or(T::ZERO)
+}
+
+pub fn parallel_sum_of_squares<T: SimdElement>(data: &[T], num_threads: usize) -> T {
+    parallel_reduce(
+        data.len(),
+        num_threads,
+        |r| sum_of_squares(&data[r]),
+        T::lane_add,
+    )
+    .unwrap_or(T::ZERO)
+}
+
+// Parallel dot product of two equally long slices; panics if the lengths differ
+pub fn parallel_dot<T: SimdElement>(a: &[T], b: &[T], num_threads: usize) -> T {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "dot product of slices with different lengths"
+    );
+    parallel_reduce(
+        a.len(),
+        num_threads,
+        |r| dot_unchecked(&a[r.clone()], &b[r]),
+        T::lane_add,
+    )
+    .unwrap_or(T::ZERO)
+}
+
+pub fn parallel_min_max<T: SimdElement>(data: &[T], num_threads: usize) -> Option<(T, T)> {
+    parallel_reduce(
+        data.len(),
+        num_threads,
+        |r| min_max(&data[r]),
+        |a, b| match (a, b) {
+            (Some((lo_a, hi_a)), Some((lo_b, hi_b))) => {
+                Some((lo_a.lane_min(lo_b), hi_a.lane_max(hi_b)))
+            }
+            (a, b) => a.or(b),
+        },
+    )
+    .flatten()
+}
+
+// Explicit `core::arch` kernels. Integer lanes wrap like the portable kernels; floats accumulate
+// per lane, so sums may round differently from a sequential loop.
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    macro_rules! float_kernels {
+        ($feature:literal, $t:ty, $lanes:literal, $vec:ty,
+         $setzero:ident, $set1:ident, $load:ident, $store:ident,
+         $add:ident, $mul:ident, $min:ident, $max:ident) => {
+            use std::arch::x86_64::*;
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn lanes(v: $vec) -> [$t; $lanes] {
+                let mut out = [0.0; $lanes];
+                $store(out.as_mut_ptr(), v);
+                out
+            }
+
+            #[target_feature(enable = $feature)]
+            pub unsafe fn sum(data: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks = data.chunks_exact($lanes);
+                for chunk in &mut chunks {
+                    acc = $add(acc, $load(chunk.as_ptr()));
+                }
+                let total = lanes(acc).into_iter().fold(0.0, |a, b| a + b);
+                chunks.remainder().iter().fold(total, |a, &x| a + x)
+            }
+
+            #[target_feature(enable = $feature)]
+            pub unsafe fn sum_of_squares(data: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks = data.chunks_exact($lanes);
+                for chunk in &mut chunks {
+                    let v = $load(chunk.as_ptr());
+                    acc = $add(acc, $mul(v, v));
+                }
+                let total = lanes(acc).into_iter().fold(0.0, |a, b| a + b);
+                chunks.remainder().iter().fold(total, |a, &x| a + x * x)
+            }
+
+            // `a` and `b` have the same length
+            #[target_feature(enable = $feature)]
+            pub unsafe fn dot(a: &[$t], b: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks_a = a.chunks_exact($lanes);
+                let mut chunks_b = b.chunks_exact($lanes);
+                for (x, y) in (&mut chunks_a).zip(&mut chunks_b) {
+                    acc = $add(acc, $mul($load(x.as_ptr()), $load(y.as_ptr())));
+                }
+                let total = lanes(acc).into_iter().fold(0.0, |a, b| a + b);
+                chunks_a
+                    .remainder()
+                    .iter()
+                    .zip(chunks_b.remainder())
+                    .fold(total, |acc, (&x, &y)| acc + x * y)
+            }
+
+            #[target_feature(enable = $feature)]
+            pub unsafe fn min_max(data: &[$t]) -> Option<($t, $t)> {
+                let first = *data.first()?;
+                // The min/max instructions return the second operand when either is NaN, so with the
+                // accumulator second and starting at infinity, NaN elements never reach it
+                let mut lo = $set1(<$t>::INFINITY);
+                let mut hi = $set1(<$t>::NEG_INFINITY);
+                let mut chunks = data.chunks_exact($lanes);
+                for chunk in &mut chunks {
+                    let v = $load(chunk.as_ptr());
+                    lo = $min(v, lo);
+                    hi = $max(v, hi);
+                }
+                let lo = lanes(lo).into_iter().fold(<$t>::INFINITY, <$t>::min);
+                let hi = lanes(hi).into_iter().fold(<$t>::NEG_INFINITY, <$t>::max);
+                let (lo, hi) = chunks
+                    .remainder()
+                    .iter()
+                    .fold((lo, hi), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+                // Only NaN seen: the bounds never moved, report NaN like the portable kernel
+                if lo > hi {
+                    Some((first, first))
+                } else {
+                    Some((lo, hi))
+                }
+            }
+        };
+    }
+
+    macro_rules! int_kernels {
+        ($feature:literal, $t:ty, $lanes:literal, $vec:ty,
+         $setzero:ident, $load:ident, $store:ident,
+         $add:ident, $mul_epu32:ident, $srli:ident, $slli:ident) => {
+            use std::arch::x86_64::*;
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn load(chunk: &[$t]) -> $vec {
+                $load(chunk.as_ptr() as *const $vec)
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn lanes(v: $vec) -> [$t; $lanes] {
+                let mut out = [0; $lanes];
+                $store(out.as_mut_ptr() as *mut $vec, v);
+                out
+            }
+
+            // Low 64 bits of each lane product, built from 32x32->64 multiplies since there is no
+            // 64-bit lane multiply before AVX-512. Identical for signed and unsigned lanes.
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn mul_lo(a: $vec, b: $vec) -> $vec {
+                let low = $mul_epu32(a, b);
+                let cross = $add($mul_epu32($srli::<32>(a), b), $mul_epu32(a, $srli::<32>(b)));
+                $add(low, $slli::<32>(cross))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn horizontal_sum(v: $vec) -> $t {
+                lanes(v).into_iter().fold(0, <$t>::wrapping_add)
+            }
+
+            #[target_feature(enable = $feature)]
+            pub unsafe fn sum(data: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks = data.chunks_exact($lanes);
+                for chunk in &mut chunks {
+                    acc = $add(acc, load(chunk));
+                }
+                chunks
+                    .remainder()
+                    .iter()
+                    .fold(horizontal_sum(acc), |a, &x| a.wrapping_add(x))
+            }
+
+            #[target_feature(enable = $feature)]
+            pub unsafe fn sum_of_squares(data: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks = data.chunks_exact($lanes);
+                for chunk in &mut chunks {
+                    let v = load(chunk);
+                    acc = $add(acc, mul_lo(v, v));
+                }
+                chunks
+                    .remainder()
+                    .iter()
+                    .fold(horizontal_sum(acc), |a, &x| {
+                        a.wrapping_add(x.wrapping_mul(x))
+                    })
+            }
+
+            // `a` and `b` have the same length
+            #[target_feature(enable = $feature)]
+            pub unsafe fn dot(a: &[$t], b: &[$t]) -> $t {
+                let mut acc = $setzero();
+                let mut chunks_a = a.chunks_exact($lanes);
+                let mut chunks_b = b.chunks_exact($lanes);
+                for (x, y) in (&mut chunks_a).zip(&mut chunks_b) {
+                    acc = $add(acc, mul_lo(load(x), load(y)));
+                }
+                chunks_a
+                    .remainder()
+                    .iter()
+                    .zip(chunks_b.remainder())
+                    .fold(horizontal_sum(acc), |acc, (&x, &y)| {
+                        acc.wrapping_add(x.wrapping_mul(y))
+                    })
+            }
+        };
+    }
+
+    // 64-bit lane compares need AVX2 (`vpcmpgtq`); `$bias` flips the sign bit so the signed
+    // compare also orders unsigned lanes
+    macro_rules! avx2_int_min_max {
+        ($t:ty, $bias:expr) => {
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn min_max(data: &[$t]) -> Option<($t, $t)> {
+                let first = *data.first()?;
+                let bias = _mm256_set1_epi64x($bias);
+                let mut lo = load(&[first; 4]);
+                let mut hi = lo;
+                let mut chunks = data.chunks_exact(4);
+                for chunk in &mut chunks {
+                    let v = load(chunk);
+                    let biased = _mm256_xor_si256(v, bias);
+                    let below = _mm256_cmpgt_epi64(_mm256_xor_si256(lo, bias), biased);
+                    let above = _mm256_cmpgt_epi64(biased, _mm256_xor_si256(hi, bias));
+                    lo = _mm256_blendv_epi8(lo, v, below);
+                    hi = _mm256_blendv_epi8(hi, v, above);
+                }
+                let lo = lanes(lo).into_iter().fold(first, Ord::min);
+                let hi = lanes(hi).into_iter().fold(first, Ord::max);
+                Some(
+                    chunks
+                        .remainder()
+                        .iter()
+                        .fold((lo, hi), |(lo, hi), &x| (lo.min(x), hi.max(x))),
+                )
+            }
+        };
+    }
+
+    // SSE4.1 has no 64-bit lane compare (that is SSE4.2), so integer min/max stays portable there
+    macro_rules! portable_min_max {
+        ($t:ty) => {
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn min_max(data: &[$t]) -> Option<($t, $t)> {
+                crate::multi_thread_processor::simd::min_max_kernel(data)
+            }
+        };
+    }
+
+    pub mod avx2 {
+        pub mod float64 {
+            float_kernels!(
+                "avx2",
+                f64,
+                4,
+                __m256d,
+                _mm256_setzero_pd,
+                _mm256_set1_pd,
+                _mm256_loadu_pd,
+                _mm256_storeu_pd,
+                _mm256_add_pd,
+                _mm256_mul_pd,
+                _mm256_min_pd,
+                _mm256_max_pd
+            );
+        }
+
+        pub mod float32 {
+            float_kernels!(
+                "avx2",
+                f32,
+                8,
+                __m256,
+                _mm256_setzero_ps,
+                _mm256_set1_ps,
+                _mm256_loadu_ps,
+                _mm256_storeu_ps,
+                _mm256_add_ps,
+                _mm256_mul_ps,
+                _mm256_min_ps,
+                _mm256_max_ps
+            );
+        }
+
+        pub mod uint64 {
+            int_kernels!(
+                "avx2",
+                u64,
+                4,
+                __m256i,
+                _mm256_setzero_si256,
+                _mm256_loadu_si256,
+                _mm256_storeu_si256,
+                _mm256_add_epi64,
+                _mm256_mul_epu32,
+                _mm256_srli_epi64,
+                _mm256_slli_epi64
+            );
+            avx2_int_min_max!(u64, i64::MIN);
+        }
+
+        pub mod int64 {
+            int_kernels!(
+                "avx2",
+                i64,
+                4,
+                __m256i,
+                _mm256_setzero_si256,
+                _mm256_loadu_si256,
+                _mm256_storeu_si256,
+                _mm256_add_epi64,
+                _mm256_mul_epu32,
+                _mm256_srli_epi64,
+                _mm256_slli_epi64
+            );
+            avx2_int_min_max!(i64, 0);
+        }
+    }
+
+    pub mod sse41 {
+        pub mod float64 {
+            float_kernels!(
+                "sse4.1",
+                f64,
+                2,
+                __m128d,
+                _mm_setzero_pd,
+                _mm_set1_pd,
+                _mm_loadu_pd,
+                _mm_storeu_pd,
+                _mm_add_pd,
+                _mm_mul_pd,
+                _mm_min_pd,
+                _mm_max_pd
+            );
+        }
+
+        pub mod float32 {
+            float_kernels!(
+                "sse4.1",
+                f32,
+                4,
+                __m128,
+                _mm_setzero_ps,
+                _mm_set1_ps,
+                _mm_loadu_ps,
+                _mm_storeu_ps,
+                _mm_add_ps,
+                _mm_mul_ps,
+                _mm_min_ps,
+                _mm_max_ps
+            );
+        }
+
+        pub mod uint64 {
+            int_kernels!(
+                "sse4.1",
+                u64,
+                2,
+                __m128i,
+                _mm_setzero_si128,
+                _mm_loadu_si128,
+                _mm_storeu_si128,
+                _mm_add_epi64,
+                _mm_mul_epu32,
+                _mm_srli_epi64,
+                _mm_slli_epi64
+            );
+            portable_min_max!(u64);
+        }
+
+        pub mod int64 {
+            int_kernels!(
+                "sse4.1",
+                i64,
+                2,
+                __m128i,
+                _mm_setzero_si128,
+                _mm_loadu_si128,
+                _mm_storeu_si128,
+                _mm_add_epi64,
+                _mm_mul_epu32,
+                _mm_srli_epi64,
+                _mm_slli_epi64
+            );
+            portable_min_max!(i64);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::panic;
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::sum_of_squares;
+
+// Shape of the pipeline. Peak memory is about (queue_capacity + num_workers + 1) * chunk_size elements.
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    pub chunk_size: usize,     // Elements per chunk
+    pub num_workers: usize,    // Threads reducing chunks
+    pub queue_capacity: usize, // Chunks that may wait in the queue before the reader blocks
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig {
+            chunk_size: 1 << 20,
+            num_workers: 12,
+            queue_capacity: 24,
+        }
+    }
+}
+
+// Core pipeline: the calling thread pulls chunks from `next_chunk` and sends them through a bounded
+// channel to the workers, each worker folds chunks into its own accumulator and the accumulators are
+// combined at the end. The first error from the source stops reading and is returned. If every worker
+// has panicked the send fails, reading stops and the worker panic is re-raised on the calling thread.
+fn run_pipeline<T, A, E>(
+    mut next_chunk: impl FnMut() -> Result<Option<Vec<T>>, E>,
+    config: &StreamConfig,
+    init: impl Fn() -> A + Sync,
+    fold: impl Fn(A, &[T]) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> Result<A, E>
+where
+    T: Send,
+    A: Send,
+{
+    let (sender, receiver) = mpsc::sync_channel::<Vec<T>>(config.queue_capacity);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let (init, fold) = (&init, &fold);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..config.num_workers.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                scope.spawn(move || {
+                    let mut acc = init();
+                    loop {
+                        // Hold the lock only while taking the next chunk, not while folding it
+                        let chunk = receiver.lock().unwrap().recv();
+                        match chunk {
+                            Ok(chunk) => acc = fold(acc, &chunk),
+                            Err(_) => return acc, // Sender dropped: the source is exhausted
+                        }
+                    }
+                })
+            })
+            .collect();
+        // Only the workers may hold the receiver, otherwise a send blocks forever once they are all gone
+        drop(receiver);
+
+        let read_result = loop {
+            match next_chunk() {
+                Ok(Some(chunk)) => {
+                    if sender.send(chunk).is_err() {
+                        break Ok(()); // Every worker has exited: the join below re-raises the panic
+                    }
+                }
+                Ok(None) => break Ok(()),
+                Err(err) => break Err(err),
+            }
+        };
+        drop(sender);
+
+        let acc = handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .reduce(combine)
+            .unwrap();
+        read_result.map(|_| acc)
+    })
+}
+
+// Reduce a fallible stream of values in chunks across worker threads
+pub fn try_stream_reduce<T, A, E>(
+    source: impl IntoIterator<Item = Result<T, E>>,
+    config: &StreamConfig,
+    init: impl Fn() -> A + Sync,
+    fold: impl Fn(A, &[T]) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> Result<A, E>
+where
+    T: Send,
+    A: Send,
+{
+    let chunk_size = config.chunk_size.max(1);
+    let mut source = source.into_iter();
+    let next_chunk = || {
+        let mut chunk = Vec::with_capacity(chunk_size);
+        for value in source.by_ref().take(chunk_size) {
+            chunk.push(value?);
+        }
+        Ok(if chunk.is_empty() { None } else { Some(chunk) })
+    };
+    run_pipeline(next_chunk, config, init, fold, combine)
+}
+
+// Reduce a stream of values in chunks across worker threads
+pub fn stream_reduce<T, A>(
+    source: impl IntoIterator<Item = T>,
+    config: &StreamConfig,
+    init: impl Fn() -> A + Sync,
+    fold: impl Fn(A, &[T]) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> A
+where
+    T: Send,
+    A: Send,
+{
+    let source = source.into_iter().map(Ok::<T, Infallible>);
+    match try_stream_reduce(source, config, init, fold, combine) {
+        Ok(acc) => acc,
+        Err(never) => match never {},
+    }
+}
+
+pub fn stream_sum_of_squares(source: impl IntoIterator<Item = u64>, config: &StreamConfig) -> u64 {
+    stream_reduce(
+        source,
+        config,
+        || 0,
+        |acc, chunk| acc + sum_of_squares(chunk),
+        |a, b| a + b,
+    )
+}
+
+// Fill `buf` as far as possible, returning the number of bytes read (less than `buf.len()` only at end of input)
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+// Reduce little-endian `u64` values read from `reader` in chunks, without loading the whole input.
+// Trailing bytes that do not form a whole value are reported as `InvalidData`.
+pub fn stream_reduce_reader<A: Send>(
+    mut reader: impl Read,
+    config: &StreamConfig,
+    init: impl Fn() -> A + Sync,
+    fold: impl Fn(A, &[u64]) -> A + Sync,
+    combine: impl Fn(A, A) -> A,
+) -> io::Result<A> {
+    const WIDTH: usize = std::mem::size_of::<u64>();
+    let mut buf = vec![0u8; config.chunk_size.max(1) * WIDTH];
+    let mut offset = 0u64;
+    let next_chunk = || {
+        let filled = read_full(&mut reader, &mut buf)?;
+        if filled % WIDTH != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "input truncated: {} trailing bytes at offset {}",
+                    filled % WIDTH,
+                    offset + (filled - filled % WIDTH) as u64
+                ),
+            ));
+        }
+        offset += filled as u64;
+        if filled == 0 {
+            return Ok(None);
+        }
+        let chunk = buf[..filled]
+            .chunks_exact(WIDTH)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        Ok(Some(chunk))
+    };
+    run_pipeline(next_chunk, config, init, fold, combine)
+}
+
+// Sum of squares of a file of little-endian `u64` values, in constant memory
+pub fn stream_file_sum_of_squares(
+    path: impl AsRef<Path>,
+    config: &StreamConfig,
+) -> io::Result<u64> {
+    let reader = BufReader::new(File::open(path)?);
+    stream_reduce_reader(
+        reader,
+        config,
+        || 0,
+        |acc, chunk| acc + sum_of_squares(chunk),
+        |a, b| a + b,
+    )
+}
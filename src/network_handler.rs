@@ -43,10 +43,8 @@ fn handle_connection(stream: TcpStream, sender: mpsc::Sender<String>) {
     let reader = BufReader::new(&stream);
 
     // Read data from the connection and send it to the processing thread
-    for line in reader.lines() {
-        if let Ok(data) = line {
-            // Send the data to the processing thread
-            sender.send(data).unwrap();
-        }
+    for data in reader.lines().map_while(Result::ok) {
+        // Send the data to the processing thread
+        sender.send(data).unwrap();
     }
 }
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::thread;
+
+use crate::multi_thread_processor::chunk_ranges;
+
+// Tile edge for the blocked kernels: three 64x64 f64 tiles fit comfortably in L2
+const BLOCK: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    // The operands' shapes are incompatible, as (rows, cols)
+    DimensionMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    // The data length does not match rows * cols
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::DimensionMismatch { left, right } => write!(
+                f,
+                "cannot multiply a {}x{} matrix by a {}x{} operand",
+                left.0, left.1, right.0, right.1
+            ),
+            MatrixError::InvalidLength { expected, actual } => {
+                write!(f, "expected {} elements, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+// Dense matrix of f64 in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+// Split `data` (rows of `cols` elements) into one block of whole rows per thread, paired with its first row
+fn row_blocks(data: &mut [f64], cols: usize, num_threads: usize) -> Vec<(usize, &mut [f64])> {
+    let rows = data.len().checked_div(cols).unwrap_or(0);
+    let mut rest = data;
+    let mut blocks = vec![];
+    for range in chunk_ranges(rows, num_threads) {
+        if range.is_empty() {
+            continue;
+        }
+        let (block, tail) = std::mem::take(&mut rest).split_at_mut(range.len() * cols);
+        blocks.push((range.start, block));
+        rest = tail;
+    }
+    blocks
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Self::from_fn(n, n, |r, c| if r == c { 1.0 } else { 0.0 })
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> f64) -> Self {
+        let data = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<f64>) -> Result<Self, MatrixError> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::InvalidLength {
+                expected: rows * cols,
+                actual: data.len(),
+            });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.data[row * self.cols + col]
+    }
+
+    pub fn row(&self, row: usize) -> &[f64] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
+
+    fn check_multiply(&self, other: &Matrix) -> Result<(), MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch {
+                left: (self.rows, self.cols),
+                right: (other.rows, other.cols),
+            });
+        }
+        Ok(())
+    }
+
+    // Textbook triple loop on the calling thread, the reference for `multiply`
+    pub fn multiply_naive(&self, other: &Matrix) -> Result<Matrix, MatrixError> {
+        self.check_multiply(other)?;
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                let mut sum = 0.0;
+                for k in 0..self.cols {
+                    sum += self.get(i, k) * other.get(k, j);
+                }
+                result.data[i * other.cols + j] = sum;
+            }
+        }
+        Ok(result)
+    }
+
+    // Blocked multiply: each thread owns a band of output rows and walks it tile by tile
+    pub fn multiply(&self, other: &Matrix, num_threads: usize) -> Result<Matrix, MatrixError> {
+        self.check_multiply(other)?;
+        let (n, m) = (self.cols, other.cols);
+        let mut result = Matrix::zeros(self.rows, m);
+
+        thread::scope(|scope| {
+            for (first_row, band) in row_blocks(&mut result.data, m, num_threads) {
+                scope.spawn(move || {
+                    let band_rows = band.len() / m;
+                    for kk in (0..n).step_by(BLOCK) {
+                        for jj in (0..m).step_by(BLOCK) {
+                            let (k_end, j_end) = ((kk + BLOCK).min(n), (jj + BLOCK).min(m));
+                            for i in 0..band_rows {
+                                let a_row = self.row(first_row + i);
+                                let out = &mut band[i * m + jj..i * m + j_end];
+                                for (k, &a) in a_row.iter().enumerate().take(k_end).skip(kk) {
+                                    let b_row = &other.row(k)[jj..j_end];
+                                    for (o, &b) in out.iter_mut().zip(b_row) {
+                                        *o += a * b;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(result)
+    }
+
+    // Element-by-element transpose on the calling thread, the reference for `transpose`
+    pub fn transpose_naive(&self) -> Matrix {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self.get(c, r))
+    }
+
+    // Blocked transpose: each thread fills a band of output rows tile by tile
+    pub fn transpose(&self, num_threads: usize) -> Matrix {
+        let mut result = Matrix::zeros(self.cols, self.rows);
+        let out_cols = self.rows;
+
+        thread::scope(|scope| {
+            for (first_row, band) in row_blocks(&mut result.data, out_cols, num_threads) {
+                scope.spawn(move || {
+                    let band_rows = band.len() / out_cols;
+                    for ii in (0..band_rows).step_by(BLOCK) {
+                        for jj in (0..out_cols).step_by(BLOCK) {
+                            for i in ii..(ii + BLOCK).min(band_rows) {
+                                for j in jj..(jj + BLOCK).min(out_cols) {
+                                    band[i * out_cols + j] = self.get(j, first_row + i);
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        result
+    }
+
+    // Matrix-vector product, each thread computing a band of the output
+    pub fn mul_vec(&self, vector: &[f64], num_threads: usize) -> Result<Vec<f64>, MatrixError> {
+        if vector.len() != self.cols {
+            return Err(MatrixError::DimensionMismatch {
+                left: (self.rows, self.cols),
+                right: (vector.len(), 1),
+            });
+        }
+        let mut result = vec![0.0; self.rows];
+
+        thread::scope(|scope| {
+            for (first_row, band) in row_blocks(&mut result, 1, num_threads) {
+                scope.spawn(move || {
+                    for (i, out) in band.iter_mut().enumerate() {
+                        *out = self
+                            .row(first_row + i)
+                            .iter()
+                            .zip(vector)
+                            .map(|(a, b)| a * b)
+                            .sum();
+                    }
+                });
+            }
+        });
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Counters a stage worker hands back when it finishes
+struct WorkerStats {
+    items: u64,
+    busy: Duration,
+    finished: Instant,
+}
+
+// A launched stage waiting to be joined
+struct RunningStage {
+    name: String,
+    started: Instant,
+    handles: Vec<JoinHandle<WorkerStats>>,
+}
+
+// Spawns every stage up to this point and returns the receiving end of the last one
+type Launch<T> = Box<dyn FnOnce(&mut Vec<RunningStage>) -> Receiver<T> + Send>;
+
+// Throughput of one stage
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageStats {
+    pub name: String,
+    pub workers: usize,
+    pub items: u64,     // Items the stage produced (source) or consumed (map and sink)
+    pub busy: Duration, // Time spent inside the stage function, summed over workers
+    pub elapsed: Duration, // From launch until the last worker finished
+}
+
+impl StageStats {
+    // Items per second over the stage's wall-clock time
+    pub fn throughput(&self) -> f64 {
+        self.items as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineReport {
+    pub stages: Vec<StageStats>,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<16} {:>7} {:>10} {:>12} {:>12} {:>14}",
+            "stage", "workers", "items", "busy", "elapsed", "items/s"
+        )?;
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:<16} {:>7} {:>10} {:>12} {:>12} {:>14.0}",
+                stage.name,
+                stage.workers,
+                stage.items,
+                format!("{:.2?}", stage.busy),
+                format!("{:.2?}", stage.elapsed),
+                stage.throughput()
+            )?;
+        }
+        write!(f, "total {:.2?}", self.elapsed)
+    }
+}
+
+// Staged pipeline: a source thread, map stages with their own worker counts, and a sink on the
+// calling thread, connected by bounded channels. Nothing runs until `sink` or `collect` is called.
+// Stages with more than one worker do not preserve item order.
+pub struct Pipeline<T> {
+    launch: Launch<T>,
+    capacity: usize,
+}
+
+impl<T: Send + 'static> Pipeline<T> {
+    // Start a pipeline from an iterator; `capacity` bounds every channel in the pipeline
+    pub fn source<I>(items: I, capacity: usize) -> Self
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+    {
+        let launch: Launch<T> = Box::new(move |stages| {
+            let (sender, receiver) = mpsc::sync_channel(capacity);
+            let started = Instant::now();
+            let handle = thread::spawn(move || {
+                let mut stats = WorkerStats {
+                    items: 0,
+                    busy: Duration::ZERO,
+                    finished: started,
+                };
+                let mut items = items.into_iter();
+                loop {
+                    let start = Instant::now();
+                    let next = items.next();
+                    stats.busy += start.elapsed();
+                    // Stop at the end of the input, or early if every downstream worker is gone
+                    let Some(item) = next else { break };
+                    if sender.send(item).is_err() {
+                        break;
+                    }
+                    stats.items += 1;
+                }
+                stats.finished = Instant::now();
+                stats
+            });
+            stages.push(RunningStage {
+                name: String::from("source"),
+                started,
+                handles: vec![handle],
+            });
+            receiver
+        });
+        Pipeline { launch, capacity }
+    }
+
+    // Add a stage that applies `f` to every item on `workers` threads
+    pub fn map<U, F>(self, name: &str, workers: usize, f: F) -> Pipeline<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> U + Send + Sync + 'static,
+    {
+        let (previous, capacity, name) = (self.launch, self.capacity, name.to_string());
+        let launch: Launch<U> = Box::new(move |stages| {
+            let input = Arc::new(Mutex::new(previous(stages)));
+            let (sender, receiver) = mpsc::sync_channel(capacity);
+            let f = Arc::new(f);
+            let started = Instant::now();
+
+            let handles = (0..workers.max(1))
+                .map(|_| {
+                    let (input, sender, f) = (Arc::clone(&input), sender.clone(), Arc::clone(&f));
+                    thread::spawn(move || map_worker(&input, &sender, &*f))
+                })
+                .collect();
+
+            stages.push(RunningStage {
+                name,
+                started,
+                handles,
+            });
+            receiver
+        });
+        Pipeline { launch, capacity }
+    }
+
+    // Run the pipeline, feeding every output item to `f` on the calling thread
+    pub fn sink(self, mut f: impl FnMut(T)) -> PipelineReport {
+        let started = Instant::now();
+        let mut stages = vec![];
+        let receiver = (self.launch)(&mut stages);
+
+        let mut sink = WorkerStats {
+            items: 0,
+            busy: Duration::ZERO,
+            finished: started,
+        };
+        for item in receiver {
+            let start = Instant::now();
+            f(item);
+            sink.busy += start.elapsed();
+            sink.items += 1;
+        }
+        sink.finished = Instant::now();
+
+        let mut report: Vec<StageStats> = stages
+            .into_iter()
+            .map(|stage| {
+                let workers: Vec<WorkerStats> = stage
+                    .handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .collect();
+                stage_stats(stage.name, stage.started, &workers)
+            })
+            .collect();
+        report.push(stage_stats(String::from("sink"), started, &[sink]));
+
+        PipelineReport {
+            stages: report,
+            elapsed: started.elapsed(),
+        }
+    }
+
+    // Run the pipeline and gather the output items
+    pub fn collect(self) -> (Vec<T>, PipelineReport) {
+        let mut items = vec![];
+        let report = self.sink(|item| items.push(item));
+        (items, report)
+    }
+}
+
+fn map_worker<T, U>(
+    input: &Mutex<Receiver<T>>,
+    output: &SyncSender<U>,
+    f: &impl Fn(T) -> U,
+) -> WorkerStats {
+    let mut stats = WorkerStats {
+        items: 0,
+        busy: Duration::ZERO,
+        finished: Instant::now(),
+    };
+    loop {
+        // Hold the lock only while taking the next item
+        let item = input.lock().unwrap().recv();
+        let Ok(item) = item else { break };
+        let start = Instant::now();
+        let result = f(item);
+        stats.busy += start.elapsed();
+        stats.items += 1;
+        if output.send(result).is_err() {
+            break;
+        }
+    }
+    stats.finished = Instant::now();
+    stats
+}
+
+fn stage_stats(name: String, started: Instant, workers: &[WorkerStats]) -> StageStats {
+    let finished = workers.iter().map(|w| w.finished).max().unwrap_or(started);
+    StageStats {
+        name,
+        workers: workers.len(),
+        items: workers.iter().map(|w| w.items).sum(),
+        busy: workers.iter().map(|w| w.busy).sum(),
+        elapsed: finished.saturating_duration_since(started),
+    }
+}
@@ -1,24 +1,60 @@
-use std::sync::{Arc, Mutex};
+pub mod concurrent_map;
+pub mod counter;
+pub mod deadlock;
+pub mod event_log;
+pub mod instrumented_mutex;
+pub mod lock_free;
+pub mod poison;
+pub mod snapshot;
+pub mod sync_primitives;
+pub mod thread_local_accumulator;
+
+use std::sync::Arc;
 use std::thread;
+use std::time::Instant;
+
+use event_log::{CounterEvent, EventLog};
+use instrumented_mutex::InstrumentedMutex;
+use poison::PoisonPolicyExt;
 
-pub fn demonstrate_shared_memory_concurrency() {
-    // Create a shared mutable integer, wrapped in a Mutex to ensure thread safety
-    let shared_data = Arc::new(Mutex::new(0));
+// The shared integer plus the log of updates made to it, both only touched under the lock
+struct SharedData {
+    value: i32,
+    events: Vec<CounterEvent>,
+}
+
+// Increment a shared integer from `threads` threads, `increments` times each, recording every update
+pub fn record_shared_memory_concurrency(threads: usize, increments: usize) -> EventLog {
+    let start_time = Instant::now();
+    // Create a shared mutable integer, wrapped in a Mutex to ensure thread safety.
+    // The instrumented wrapper records how long each thread waited for it.
+    let shared_data = Arc::new(InstrumentedMutex::new(
+        "shared_memory_concurrency::shared_data",
+        SharedData {
+            value: 0,
+            events: Vec::with_capacity(threads * increments),
+        },
+    ));
 
     // Start multiple threads to increment the shared integer value
-    let handles: Vec<_> = (0..5)
-        .map(|_| {
+    let handles: Vec<_> = (0..threads)
+        .map(|worker| {
             let shared_data = Arc::clone(&shared_data);
             thread::spawn(move || {
-                for _ in 0..10 {
-                    // Use the lock method of Mutex to acquire the lock and access the shared data
-                    let mut data = shared_data.lock().unwrap();
-                    *data += 1;
-                    println!(
-                        "Thread {:?} updated shared data: {}",
-                        thread::current().id(),
-                        *data
-                    );
+                for _ in 0..increments {
+                    // Acquire the lock to access the shared data, a panicking holder cannot leave
+                    // a half-done increment behind so the value is recovered rather than propagated
+                    let mut data = shared_data.lock_or_recover();
+                    data.value += 1;
+                    // Logged under the same lock, so the sequence is the order the updates happened in
+                    let event = CounterEvent {
+                        sequence: data.events.len(),
+                        worker,
+                        thread_id: thread::current().id(),
+                        value: data.value,
+                        at: start_time.elapsed(),
+                    };
+                    data.events.push(event);
                 }
             })
         })
@@ -29,7 +65,23 @@ pub fn demonstrate_shared_memory_concurrency() {
         handle.join().unwrap();
     }
 
+    let events = std::mem::take(&mut shared_data.lock_or_recover().events);
+    EventLog {
+        workers: threads,
+        events,
+    }
+}
+
+// Returns the final value so callers can check no increment was lost
+pub fn demonstrate_shared_memory_concurrency() -> i32 {
+    let log = record_shared_memory_concurrency(5, 10);
+
+    // Show which thread got the lock when, rather than printing from inside the threads
+    println!("Lock acquisition order:");
+    print!("{}", log.ascii_chart(80));
+
     // Print the final value of the shared data
-    let final_data = shared_data.lock().unwrap();
-    println!("Final shared data value: {}", *final_data);
+    let final_data = log.final_value().unwrap_or(0);
+    println!("Final shared data value: {}", final_data);
+    final_data
 }
@@ -0,0 +1,279 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::poison::{PoisonPolicy, PoisonPolicyExt, PoisonPolicyReadExt};
+use crate::multi_thread_processor::rng::Xoshiro256;
+
+// A key-value map shared between threads: reads take shared locks, writes take exclusive ones.
+// Values are returned by clone so no lock is held once a call returns.
+pub trait ConcurrentMap<K, V>: Send + Sync {
+    fn get(&self, key: &K) -> Option<V>;
+
+    // Returns the previous value, if any
+    fn insert(&self, key: K, value: V) -> Option<V>;
+
+    fn remove(&self, key: &K) -> Option<V>;
+
+    // Run `f` on the value in place under the write lock, `None` if the key is absent
+    fn update<R>(&self, key: &K, f: impl FnOnce(&mut V) -> R) -> Option<R>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// A lock poisoned by a panicking `update` closure is handled by the map's `PoisonPolicy`;
+// under `Propagate` every later operation on it panics with `LockPoisoned`
+fn read_map<K, V>(
+    lock: &RwLock<HashMap<K, V>>,
+    policy: PoisonPolicy,
+) -> RwLockReadGuard<'_, HashMap<K, V>> {
+    lock.read_with_reset(policy)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+fn write_map<K, V>(
+    lock: &RwLock<HashMap<K, V>>,
+    policy: PoisonPolicy,
+) -> RwLockWriteGuard<'_, HashMap<K, V>> {
+    lock.lock_with_reset(policy)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+// Every operation goes through one RwLock around the whole map
+#[derive(Debug)]
+pub struct RwLockMap<K, V> {
+    map: RwLock<HashMap<K, V>>,
+    policy: PoisonPolicy,
+}
+
+impl<K, V> RwLockMap<K, V> {
+    pub fn new() -> Self {
+        RwLockMap {
+            map: RwLock::new(HashMap::new()),
+            policy: PoisonPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: PoisonPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<K, V> Default for RwLockMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for RwLockMap<K, V>
+where
+    K: Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        read_map(&self.map, self.policy).get(key).cloned()
+    }
+
+    fn insert(&self, key: K, value: V) -> Option<V> {
+        write_map(&self.map, self.policy).insert(key, value)
+    }
+
+    fn remove(&self, key: &K) -> Option<V> {
+        write_map(&self.map, self.policy).remove(key)
+    }
+
+    fn update<R>(&self, key: &K, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        write_map(&self.map, self.policy).get_mut(key).map(f)
+    }
+
+    fn len(&self) -> usize {
+        read_map(&self.map, self.policy).len()
+    }
+}
+
+// Keys are spread over independent RwLock-protected shards, so writers only block their own shard
+#[derive(Debug)]
+pub struct ShardedMap<K, V> {
+    hasher: RandomState,
+    shards: Box<[RwLock<HashMap<K, V>>]>,
+    policy: PoisonPolicy,
+}
+
+impl<K, V> ShardedMap<K, V> {
+    // Four shards per available CPU
+    pub fn new() -> Self {
+        Self::with_shards(4 * thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
+        ShardedMap {
+            hasher: RandomState::new(),
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            policy: PoisonPolicy::default(),
+        }
+    }
+
+    // Poisoning only affects the shard whose lock was held, so `ResetToDefault` clears just that shard
+    pub fn with_policy(mut self, policy: PoisonPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+}
+
+impl<K: Hash, V> ShardedMap<K, V> {
+    fn shard(&self, key: &K) -> &RwLock<HashMap<K, V>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+impl<K, V> Default for ShardedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ConcurrentMap<K, V> for ShardedMap<K, V>
+where
+    K: Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        read_map(self.shard(key), self.policy).get(key).cloned()
+    }
+
+    fn insert(&self, key: K, value: V) -> Option<V> {
+        write_map(self.shard(&key), self.policy).insert(key, value)
+    }
+
+    fn remove(&self, key: &K) -> Option<V> {
+        write_map(self.shard(key), self.policy).remove(key)
+    }
+
+    fn update<R>(&self, key: &K, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        write_map(self.shard(key), self.policy).get_mut(key).map(f)
+    }
+
+    // Shards are counted one after another, so concurrent writes may or may not be included
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| read_map(shard, self.policy).len())
+            .sum()
+    }
+}
+
+// Workload for `run_map_benchmark`
+#[derive(Debug, Clone)]
+pub struct MapWorkload {
+    pub threads: usize,
+    pub operations_per_thread: usize,
+    pub read_ratio: f64, // Fraction of operations that are `get`, the rest are `update` or `insert`
+    pub key_space: u64,  // Keys are drawn uniformly from 0..key_space
+    pub seed: u64,
+}
+
+impl Default for MapWorkload {
+    fn default() -> Self {
+        MapWorkload {
+            threads: 8,
+            operations_per_thread: 100_000,
+            read_ratio: 0.9,
+            key_space: 10_000,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapBenchmarkResult {
+    pub backend: &'static str,
+    pub read_ratio: f64,
+    pub operations: usize,
+    pub elapsed: Duration,
+}
+
+impl MapBenchmarkResult {
+    pub fn operations_per_sec(&self) -> f64 {
+        self.operations as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+// Prefill `map` with the workload's key space, then time a random read/write mix from every thread.
+// Each thread draws its operations from its own RNG stream, so a seed always replays the same mix.
+pub fn run_map_benchmark<M>(
+    backend: &'static str,
+    map: Arc<M>,
+    workload: &MapWorkload,
+) -> MapBenchmarkResult
+where
+    M: ConcurrentMap<u64, u64> + 'static,
+{
+    for key in 0..workload.key_space {
+        map.insert(key, 0);
+    }
+    let key_space = workload.key_space.max(1);
+
+    let start_time = Instant::now();
+    let handles: Vec<_> = (0..workload.threads)
+        .map(|worker| {
+            let map = Arc::clone(&map);
+            let mut rng = Xoshiro256::stream(workload.seed, worker as u64);
+            let operations = workload.operations_per_thread;
+            let read_ratio = workload.read_ratio;
+            thread::spawn(move || {
+                for _ in 0..operations {
+                    let key = rng.next_u64() % key_space;
+                    let roll = rng.next_f64();
+                    if roll < read_ratio {
+                        map.get(&key);
+                    } else if map.update(&key, |value| *value += 1).is_none() {
+                        map.insert(key, 1);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    MapBenchmarkResult {
+        backend,
+        read_ratio: workload.read_ratio,
+        operations: workload.threads * workload.operations_per_thread,
+        elapsed: start_time.elapsed(),
+    }
+}
+
+// Run the workload against both backends for each read ratio
+pub fn compare_maps(read_ratios: &[f64], workload: &MapWorkload) -> Vec<MapBenchmarkResult> {
+    read_ratios
+        .iter()
+        .flat_map(|&read_ratio| {
+            let workload = MapWorkload {
+                read_ratio,
+                ..workload.clone()
+            };
+            [
+                run_map_benchmark("rwlock", Arc::new(RwLockMap::new()), &workload),
+                run_map_benchmark("sharded", Arc::new(ShardedMap::new()), &workload),
+            ]
+        })
+        .collect()
+}
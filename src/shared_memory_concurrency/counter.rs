@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::poison::PoisonPolicyExt;
+use super::thread_local_accumulator::ThreadLocalAccumulator;
+
+// A counter many threads can increment at once
+pub trait SharedCounter: Send + Sync {
+    fn add(&self, delta: u64);
+
+    fn increment(&self) {
+        self.add(1);
+    }
+
+    fn get(&self) -> u64;
+}
+
+// Every update takes the same lock
+#[derive(Debug, Default)]
+pub struct MutexCounter(Mutex<u64>);
+
+impl MutexCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SharedCounter for MutexCounter {
+    // A single `+=` cannot leave the count half-updated, so a panic elsewhere never invalidates it
+    fn add(&self, delta: u64) {
+        *self.0.lock_or_recover() += delta;
+    }
+
+    fn get(&self) -> u64 {
+        *self.0.lock_or_recover()
+    }
+}
+
+// Every update is a single atomic add on one shared cache line
+#[derive(Debug, Default)]
+pub struct AtomicCounter(AtomicU64);
+
+impl AtomicCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SharedCounter for AtomicCounter {
+    fn add(&self, delta: u64) {
+        self.0.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// Keeps each shard on its own cache line so neighbouring shards do not contend
+#[derive(Debug, Default)]
+#[repr(align(128))]
+struct PaddedAtomic(AtomicU64);
+
+// Striped counter: each thread updates its own shard, reads add up all shards.
+// `get` is exact once writers have finished; while they run it is a moment-in-time estimate.
+#[derive(Debug)]
+pub struct ShardedCounter {
+    shards: Box<[PaddedAtomic]>,
+}
+
+impl ShardedCounter {
+    // One shard per available CPU
+    pub fn new() -> Self {
+        Self::with_shards(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
+        ShardedCounter {
+            shards: (0..shards.max(1))
+                .map(|_| PaddedAtomic::default())
+                .collect(),
+        }
+    }
+
+    fn shard(&self) -> &AtomicU64 {
+        &self.shards[thread_slot() % self.shards.len()].0
+    }
+}
+
+impl Default for ShardedCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedCounter for ShardedCounter {
+    fn add(&self, delta: u64) {
+        self.shard().fetch_add(delta, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.0.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
+// Small per-thread number handed out round-robin, used to spread threads over shards
+pub(crate) fn thread_slot() -> usize {
+    static NEXT_SLOT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static SLOT: usize = NEXT_SLOT.fetch_add(1, Ordering::Relaxed);
+    }
+    SLOT.with(|slot| *slot)
+}
+
+// Increment `counter` from `threads` threads, `increments` times each, and return the final value and time taken
+pub fn run_counter(
+    counter: Arc<dyn SharedCounter>,
+    threads: usize,
+    increments: u64,
+) -> (u64, Duration) {
+    let start_time = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..increments {
+                    counter.increment();
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    (counter.get(), start_time.elapsed())
+}
+
+// Outcome of one backend in `compare_counters`
+#[derive(Debug, Clone)]
+pub struct CounterRun {
+    pub backend: &'static str,
+    pub final_value: u64,
+    pub elapsed: Duration,
+}
+
+impl CounterRun {
+    pub fn increments_per_sec(&self) -> f64 {
+        self.final_value as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+// Run the same contended workload against every backend
+pub fn compare_counters(threads: usize, increments: u64) -> Vec<CounterRun> {
+    let backends: [(&'static str, Arc<dyn SharedCounter>); 4] = [
+        ("mutex", Arc::new(MutexCounter::new())),
+        ("atomic", Arc::new(AtomicCounter::new())),
+        ("sharded", Arc::new(ShardedCounter::new())),
+        (
+            "thread-local",
+            Arc::new(ThreadLocalAccumulator::<u64>::new()),
+        ),
+    ];
+    backends
+        .into_iter()
+        .map(|(backend, counter)| {
+            let (final_value, elapsed) = run_counter(counter, threads, increments);
+            CounterRun {
+                backend,
+                final_value,
+                elapsed,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,256 @@
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+
+type LockId = usize;
+type Site = &'static Location<'static>;
+
+// First observation of "`to` was locked while `from` was held"
+#[derive(Debug, Clone)]
+struct OrderEdge {
+    thread: String,
+    held_at: Site,
+    acquired_at: Site,
+}
+
+// Every lock order seen so far across all threads; a cycle means two threads can deadlock
+#[derive(Debug, Default)]
+struct LockGraph {
+    names: HashMap<LockId, &'static str>,
+    edges: HashMap<LockId, HashMap<LockId, OrderEdge>>,
+}
+
+impl LockGraph {
+    // Locks on a path from `from` to `to`, both included
+    fn path(&self, from: LockId, to: LockId) -> Option<Vec<LockId>> {
+        let mut parents = HashMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(lock) = queue.pop_front() {
+            if lock == to {
+                let mut path = vec![to];
+                while *path.last().unwrap() != from {
+                    path.push(parents[path.last().unwrap()]);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in self.edges.get(&lock).into_iter().flat_map(|e| e.keys()) {
+                if let Entry::Vacant(entry) = parents.entry(next) {
+                    entry.insert(lock);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    fn describe(&self, from: LockId, to: LockId, edge: &OrderEdge) -> LockOrderEdge {
+        LockOrderEdge {
+            held: self.names[&from],
+            held_at: edge.held_at.to_string(),
+            acquired: self.names[&to],
+            acquired_at: edge.acquired_at.to_string(),
+            thread: edge.thread.clone(),
+        }
+    }
+}
+
+fn graph() -> &'static Mutex<LockGraph> {
+    static GRAPH: OnceLock<Mutex<LockGraph>> = OnceLock::new();
+    GRAPH.get_or_init(Default::default)
+}
+
+fn lock_graph() -> MutexGuard<'static, LockGraph> {
+    // The graph is only updated by this module and never across a panic
+    graph().lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+thread_local! {
+    // Debug locks the current thread holds, in acquisition order
+    static HELD: RefCell<Vec<(LockId, Site)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn thread_label() -> String {
+    let current = thread::current();
+    match current.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", current.id()),
+    }
+}
+
+// One step of a lock order cycle: `thread` locked `acquired` while holding `held`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockOrderEdge {
+    pub held: &'static str,
+    pub held_at: String,
+    pub acquired: &'static str,
+    pub acquired_at: String,
+    pub thread: String,
+}
+
+// Taking the lock would close a cycle in the lock order graph, so some interleaving of the
+// threads involved deadlocks. The first edge is the acquisition that was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadlockError {
+    pub cycle: Vec<LockOrderEdge>,
+}
+
+impl DeadlockError {
+    // Threads involved in the cycle, without duplicates
+    pub fn threads(&self) -> Vec<&str> {
+        let mut threads: Vec<&str> = vec![];
+        for edge in &self.cycle {
+            if !threads.contains(&edge.thread.as_str()) {
+                threads.push(&edge.thread);
+            }
+        }
+        threads
+    }
+}
+
+impl fmt::Display for DeadlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "potential deadlock: lock order cycle across threads {}",
+            self.threads().join(", ")
+        )?;
+        for edge in &self.cycle {
+            write!(
+                f,
+                "\n  thread `{}` locks `{}` at {} while holding `{}` locked at {}",
+                edge.thread, edge.acquired, edge.acquired_at, edge.held, edge.held_at
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DeadlockError {}
+
+// Opt-in debug replacement for `Mutex` that checks every acquisition against the lock order
+// seen so far and refuses one that could deadlock, instead of hanging when it eventually does.
+// Costs a global lock per acquisition, so keep it to debugging and tests.
+#[derive(Debug)]
+pub struct DebugMutex<T> {
+    id: LockId,
+    name: &'static str,
+    inner: Mutex<T>,
+}
+
+impl<T> DebugMutex<T> {
+    pub fn new(name: &'static str, value: T) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        lock_graph().names.insert(id, name);
+        DebugMutex {
+            id,
+            name,
+            inner: Mutex::new(value),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    // A poisoned lock is recovered rather than reported, this type only diagnoses lock ordering
+    #[track_caller]
+    pub fn lock(&self) -> Result<DebugMutexGuard<'_, T>, DeadlockError> {
+        let site = Location::caller();
+        let held = HELD.with(|held| held.borrow().clone());
+        {
+            let mut graph = lock_graph();
+            let edge = OrderEdge {
+                thread: thread_label(),
+                held_at: site,
+                acquired_at: site,
+            };
+            // Check every new edge before recording any, so a refused lock leaves the graph untouched
+            for &(held_id, held_at) in &held {
+                let new_edge = OrderEdge {
+                    held_at,
+                    ..edge.clone()
+                };
+                if let Some(path) = graph.path(self.id, held_id) {
+                    let mut cycle = vec![graph.describe(held_id, self.id, &new_edge)];
+                    cycle.extend(
+                        path.windows(2)
+                            .map(|w| graph.describe(w[0], w[1], &graph.edges[&w[0]][&w[1]])),
+                    );
+                    return Err(DeadlockError { cycle });
+                }
+            }
+            for &(held_id, held_at) in &held {
+                graph
+                    .edges
+                    .entry(held_id)
+                    .or_default()
+                    .entry(self.id)
+                    .or_insert_with(|| OrderEdge {
+                        held_at,
+                        ..edge.clone()
+                    });
+            }
+        }
+
+        let guard = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        HELD.with(|held| held.borrow_mut().push((self.id, site)));
+        Ok(DebugMutexGuard { guard, id: self.id })
+    }
+}
+
+impl<T> Drop for DebugMutex<T> {
+    fn drop(&mut self) {
+        let mut graph = lock_graph();
+        graph.names.remove(&self.id);
+        graph.edges.remove(&self.id);
+        for edges in graph.edges.values_mut() {
+            edges.remove(&self.id);
+        }
+    }
+}
+
+// Guard returned by `DebugMutex::lock`, takes the lock off the thread's held list when dropped
+pub struct DebugMutexGuard<'a, T> {
+    guard: MutexGuard<'a, T>,
+    id: LockId,
+}
+
+impl<T> Deref for DebugMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for DebugMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DebugMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for DebugMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        // Guards may be dropped out of order, remove the most recent entry for this lock
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(index) = held.iter().rposition(|&(id, _)| id == self.id) {
+                held.remove(index);
+            }
+        });
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt::Write;
+use std::thread::ThreadId;
+use std::time::Duration;
+
+// One update of the shared value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterEvent {
+    pub sequence: usize, // Position in the order the lock was taken, starting at 0
+    pub worker: usize,   // Index of the spawning loop, stable across runs unlike `thread_id`
+    pub thread_id: ThreadId,
+    pub value: i32,   // Shared value right after this update
+    pub at: Duration, // Since the demo started
+}
+
+// Every update made by a concurrency demo, in sequence order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventLog {
+    pub workers: usize,
+    pub events: Vec<CounterEvent>,
+}
+
+impl EventLog {
+    pub fn final_value(&self) -> Option<i32> {
+        self.events.last().map(|event| event.value)
+    }
+
+    // Events made by one worker, in the order it made them
+    pub fn worker_events(&self, worker: usize) -> impl Iterator<Item = &CounterEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.worker == worker)
+    }
+
+    // Replay the updates in order, one line each
+    pub fn timeline(&self) -> String {
+        let mut timeline = format!(
+            "{:>5} {:>12} {:>6} {:>14} {:>6}\n",
+            "seq", "elapsed", "worker", "thread", "value"
+        );
+        for event in &self.events {
+            let _ = writeln!(
+                timeline,
+                "{:>5} {:>12} {:>6} {:>14} {:>6}",
+                event.sequence,
+                format!("{:.2?}", event.at),
+                event.worker,
+                format!("{:?}", event.thread_id),
+                event.value
+            );
+        }
+        timeline
+    }
+
+    // One row per worker and one column per update, `#` marking whose update it was.
+    // Logs longer than `width` are folded so each column covers several consecutive updates.
+    pub fn ascii_chart(&self, width: usize) -> String {
+        let columns = self.events.len().min(width.max(1));
+        let mut chart = String::new();
+        for worker in 0..self.workers {
+            let row: String = (0..columns)
+                .map(|column| {
+                    let start = column * self.events.len() / columns;
+                    let end = (column + 1) * self.events.len() / columns;
+                    if self.events[start..end].iter().any(|e| e.worker == worker) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            let _ = writeln!(chart, "worker {:>2} |{}|", worker, row);
+        }
+        chart
+    }
+}
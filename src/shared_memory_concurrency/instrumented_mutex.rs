@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LockResult, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, TryLockError};
+use std::time::{Duration, Instant};
+
+// Upper bounds of the wait time histogram buckets, the last bucket has no upper bound
+pub const WAIT_BUCKETS: [Duration; 5] = [
+    Duration::from_micros(1),
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Instrumentation is opt-in: while disabled, `lock` is a plain `Mutex::lock` and records nothing
+pub fn set_instrumentation_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn instrumentation_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Counters for every acquisition made from one call site
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SiteStats {
+    pub acquisitions: u64,
+    pub contended: u64, // Acquisitions that found the lock already held
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+    pub total_hold: Duration,
+    pub max_hold: Duration,
+    pub wait_histogram: [u64; WAIT_BUCKETS.len() + 1],
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+// Live counterpart of `SiteStats`, updated with relaxed atomics so recording never blocks
+#[derive(Debug, Default)]
+struct SiteCounters {
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    total_wait_ns: AtomicU64,
+    max_wait_ns: AtomicU64,
+    total_hold_ns: AtomicU64,
+    max_hold_ns: AtomicU64,
+    wait_histogram: [AtomicU64; WAIT_BUCKETS.len() + 1],
+}
+
+impl SiteCounters {
+    fn record(&self, timing: &Timing, hold: Duration) {
+        let (wait, hold) = (nanos(timing.wait), nanos(hold));
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.contended
+            .fetch_add(u64::from(timing.contended), Ordering::Relaxed);
+        self.total_wait_ns.fetch_add(wait, Ordering::Relaxed);
+        self.max_wait_ns.fetch_max(wait, Ordering::Relaxed);
+        self.total_hold_ns.fetch_add(hold, Ordering::Relaxed);
+        self.max_hold_ns.fetch_max(hold, Ordering::Relaxed);
+        let bucket = WAIT_BUCKETS
+            .iter()
+            .position(|&bound| timing.wait < bound)
+            .unwrap_or(WAIT_BUCKETS.len());
+        self.wait_histogram[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Fields are read one by one, so a snapshot taken during a release may be off by that release
+    fn snapshot(&self) -> SiteStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        SiteStats {
+            acquisitions: load(&self.acquisitions),
+            contended: load(&self.contended),
+            total_wait: Duration::from_nanos(load(&self.total_wait_ns)),
+            max_wait: Duration::from_nanos(load(&self.max_wait_ns)),
+            total_hold: Duration::from_nanos(load(&self.total_hold_ns)),
+            max_hold: Duration::from_nanos(load(&self.max_hold_ns)),
+            wait_histogram: std::array::from_fn(|i| load(&self.wait_histogram[i])),
+        }
+    }
+}
+
+// Stats shared by every instrumented mutex created with the same name
+#[derive(Debug, Default)]
+struct LockStats {
+    waiting: AtomicUsize,
+    // Only written the first time a call site is seen
+    sites: RwLock<HashMap<&'static Location<'static>, Arc<SiteCounters>>>,
+}
+
+impl LockStats {
+    fn record(&self, timing: &Timing, hold: Duration) {
+        // Stats are only ever updated by this module and never while panicking, so poisoning carries no meaning here
+        if let Some(site) = self
+            .sites
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(timing.site)
+        {
+            site.record(timing, hold);
+            return;
+        }
+        self.sites
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(timing.site)
+            .or_default()
+            .record(timing, hold);
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<&'static str, Arc<LockStats>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<&'static str, Arc<LockStats>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+// A `std::sync::Mutex` that records acquisitions, wait and hold times per call site while
+// instrumentation is enabled. Mutexes sharing a name share their stats, so a lock created per call
+// still shows up as one entry. An acquisition is recorded once its guard is dropped.
+#[derive(Debug)]
+pub struct InstrumentedMutex<T> {
+    name: &'static str,
+    stats: Arc<LockStats>,
+    inner: Mutex<T>,
+}
+
+impl<T> InstrumentedMutex<T> {
+    pub fn new(name: &'static str, value: T) -> Self {
+        let stats = registry()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(name)
+            .or_default()
+            .clone();
+        InstrumentedMutex {
+            name,
+            stats,
+            inner: Mutex::new(value),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    // Same contract as `Mutex::lock`, the caller's location is recorded as the lock site
+    #[track_caller]
+    pub fn lock(&self) -> LockResult<InstrumentedMutexGuard<'_, T>> {
+        if !instrumentation_enabled() {
+            return match self.inner.lock() {
+                Ok(guard) => Ok(InstrumentedMutexGuard::new(guard, None)),
+                Err(err) => Err(PoisonError::new(InstrumentedMutexGuard::new(
+                    err.into_inner(),
+                    None,
+                ))),
+            };
+        }
+
+        let site = Location::caller();
+        let start = Instant::now();
+        let (result, contended) = match self.inner.try_lock() {
+            Ok(guard) => (Ok(guard), false),
+            Err(TryLockError::Poisoned(err)) => (Err(err), false),
+            Err(TryLockError::WouldBlock) => {
+                self.stats.waiting.fetch_add(1, Ordering::Relaxed);
+                let result = self.inner.lock();
+                self.stats.waiting.fetch_sub(1, Ordering::Relaxed);
+                (result, true)
+            }
+        };
+        let timing = Timing {
+            stats: &self.stats,
+            site,
+            wait: start.elapsed(),
+            contended,
+            acquired: Instant::now(),
+        };
+
+        match result {
+            Ok(guard) => Ok(InstrumentedMutexGuard::new(guard, Some(timing))),
+            Err(err) => Err(PoisonError::new(InstrumentedMutexGuard::new(
+                err.into_inner(),
+                Some(timing),
+            ))),
+        }
+    }
+
+    // Threads currently blocked in `lock` on any mutex sharing this one's name.
+    // Only counted while instrumentation is enabled.
+    pub fn waiting(&self) -> usize {
+        self.stats.waiting.load(Ordering::Relaxed)
+    }
+
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+
+    // Stats of every mutex sharing this one's name
+    pub fn report(&self) -> ContentionReport {
+        ContentionReport::new(self.name, &self.stats)
+    }
+}
+
+// How one acquisition went, recorded when its guard is dropped
+struct Timing<'a> {
+    stats: &'a LockStats,
+    site: &'static Location<'static>,
+    wait: Duration,
+    contended: bool,
+    acquired: Instant,
+}
+
+// Guard returned by `InstrumentedMutex::lock`, records the acquisition when dropped
+pub struct InstrumentedMutexGuard<'a, T> {
+    guard: ManuallyDrop<MutexGuard<'a, T>>,
+    timing: Option<Timing<'a>>, // `None` if instrumentation was disabled at lock time
+}
+
+impl<'a, T> InstrumentedMutexGuard<'a, T> {
+    fn new(guard: MutexGuard<'a, T>, timing: Option<Timing<'a>>) -> Self {
+        InstrumentedMutexGuard {
+            guard: ManuallyDrop::new(guard),
+            timing,
+        }
+    }
+}
+
+impl<T> Deref for InstrumentedMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for InstrumentedMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for InstrumentedMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for InstrumentedMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        // The hold time ends here, but it is recorded after unlocking so the bookkeeping stays
+        // outside the critical section
+        let hold = self.timing.as_ref().map(|timing| timing.acquired.elapsed());
+        // Safety: `guard` is dropped exactly once, here, and not touched afterwards
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
+        if let Some((timing, hold)) = self.timing.as_ref().zip(hold) {
+            timing.stats.record(timing, hold);
+        }
+    }
+}
+
+// Per-site stats of one named lock
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteReport {
+    pub site: String, // "file:line:column" of the `lock` call
+    pub stats: SiteStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentionReport {
+    pub name: &'static str,
+    pub sites: Vec<SiteReport>, // Sorted by total wait time, hottest first
+}
+
+impl ContentionReport {
+    fn new(name: &'static str, stats: &LockStats) -> Self {
+        let mut sites: Vec<SiteReport> = stats
+            .sites
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(site, counters)| SiteReport {
+                site: site.to_string(),
+                stats: counters.snapshot(),
+            })
+            .collect();
+        sites.sort_by(|a, b| {
+            b.stats
+                .total_wait
+                .cmp(&a.stats.total_wait)
+                .then_with(|| a.site.cmp(&b.site))
+        });
+        ContentionReport { name, sites }
+    }
+
+    pub fn acquisitions(&self) -> u64 {
+        self.sites.iter().map(|s| s.stats.acquisitions).sum()
+    }
+
+    pub fn contended(&self) -> u64 {
+        self.sites.iter().map(|s| s.stats.contended).sum()
+    }
+
+    pub fn total_wait(&self) -> Duration {
+        self.sites.iter().map(|s| s.stats.total_wait).sum()
+    }
+}
+
+impl fmt::Display for ContentionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "lock `{}`: {} acquisitions, {} contended, {:.2?} waiting",
+            self.name,
+            self.acquisitions(),
+            self.contended(),
+            self.total_wait()
+        )?;
+        writeln!(
+            f,
+            "  {:<40} {:>8} {:>9} {:>12} {:>12} {:>12} {:>12}",
+            "site", "acquired", "contended", "total wait", "max wait", "total hold", "max hold"
+        )?;
+        for s in &self.sites {
+            writeln!(
+                f,
+                "  {:<40} {:>8} {:>9} {:>12} {:>12} {:>12} {:>12}",
+                s.site,
+                s.stats.acquisitions,
+                s.stats.contended,
+                format!("{:.2?}", s.stats.total_wait),
+                format!("{:.2?}", s.stats.max_wait),
+                format!("{:.2?}", s.stats.total_hold),
+                format!("{:.2?}", s.stats.max_hold)
+            )?;
+            let buckets: Vec<String> = WAIT_BUCKETS
+                .iter()
+                .map(|bound| format!("<{:?}", bound))
+                .chain([format!(">={:?}", WAIT_BUCKETS[WAIT_BUCKETS.len() - 1])])
+                .zip(&s.stats.wait_histogram)
+                .map(|(label, count)| format!("{}:{}", label, count))
+                .collect();
+            writeln!(f, "    wait histogram {}", buckets.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+// Reports for every instrumented lock created so far, hottest lock first
+pub fn contention_report() -> Vec<ContentionReport> {
+    let mut reports: Vec<ContentionReport> = registry()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(name, stats)| ContentionReport::new(name, stats))
+        .collect();
+    reports.sort_by(|a, b| {
+        b.total_wait()
+            .cmp(&a.total_wait())
+            .then_with(|| a.name.cmp(b.name))
+    });
+    reports
+}
@@ -0,0 +1,206 @@
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned, Shared};
+
+// Unlinked nodes are handed to the epoch collector and freed only once no pinned thread can still
+// be reading them, so a pop never races with a free or reuses an address another thread is comparing against.
+
+struct StackNode<T> {
+    value: ManuallyDrop<T>, // Moved out by the pop that unlinks the node
+    next: Atomic<StackNode<T>>,
+}
+
+// Treiber stack: push and pop swing `head` with compare-exchange
+pub struct LockFreeStack<T> {
+    head: Atomic<StackNode<T>>,
+}
+
+// Values only ever move between threads, they are never shared
+unsafe impl<T: Send> Send for LockFreeStack<T> {}
+unsafe impl<T: Send> Sync for LockFreeStack<T> {}
+
+impl<T> LockFreeStack<T> {
+    pub fn new() -> Self {
+        LockFreeStack {
+            head: Atomic::null(),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let mut node = Owned::new(StackNode {
+            value: ManuallyDrop::new(value),
+            next: Atomic::null(),
+        });
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Relaxed, &guard);
+            node.next.store(head, Relaxed);
+            match self
+                .head
+                .compare_exchange(head, node, Release, Relaxed, &guard)
+            {
+                Ok(_) => return,
+                Err(err) => node = err.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Acquire, &guard);
+            // Safety: `head` was loaded under `guard`, so it cannot be freed before the guard is dropped
+            let node = unsafe { head.as_ref() }?;
+            let next = node.next.load(Relaxed, &guard);
+            if self
+                .head
+                .compare_exchange(head, next, Relaxed, Relaxed, &guard)
+                .is_ok()
+            {
+                // Safety: only the thread whose exchange unlinked the node reads its value, and does so once
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(ptr::read(&node.value)));
+                }
+            }
+        }
+    }
+
+    // A snapshot, another thread may push or pop right after
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Acquire, &epoch::pin()).is_null()
+    }
+}
+
+impl<T> Default for LockFreeStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for LockFreeStack<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+struct QueueNode<T> {
+    value: MaybeUninit<T>, // Uninitialised in the sentinel node `head` points at
+    next: Atomic<QueueNode<T>>,
+}
+
+// Michael-Scott queue: producers link at `tail`, consumers advance `head` past a sentinel node
+pub struct LockFreeQueue<T> {
+    head: Atomic<QueueNode<T>>,
+    tail: Atomic<QueueNode<T>>,
+}
+
+unsafe impl<T: Send> Send for LockFreeQueue<T> {}
+unsafe impl<T: Send> Sync for LockFreeQueue<T> {}
+
+impl<T> LockFreeQueue<T> {
+    pub fn new() -> Self {
+        let queue = LockFreeQueue {
+            head: Atomic::null(),
+            tail: Atomic::null(),
+        };
+        // Safety: the queue is not shared yet
+        let sentinel = Owned::new(QueueNode {
+            value: MaybeUninit::uninit(),
+            next: Atomic::null(),
+        })
+        .into_shared(unsafe { epoch::unprotected() });
+        queue.head.store(sentinel, Relaxed);
+        queue.tail.store(sentinel, Relaxed);
+        queue
+    }
+
+    pub fn push(&self, value: T) {
+        let guard = epoch::pin();
+        let node = Owned::new(QueueNode {
+            value: MaybeUninit::new(value),
+            next: Atomic::null(),
+        })
+        .into_shared(&guard);
+        loop {
+            let tail = self.tail.load(Acquire, &guard);
+            // Safety: `tail` is never null and is protected by `guard`
+            let tail_node = unsafe { tail.deref() };
+            let next = tail_node.next.load(Acquire, &guard);
+            if !next.is_null() {
+                // `tail` is lagging behind, help the other producer move it on
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, next, Release, Relaxed, &guard);
+                continue;
+            }
+            if tail_node
+                .next
+                .compare_exchange(Shared::null(), node, Release, Relaxed, &guard)
+                .is_ok()
+            {
+                let _ = self
+                    .tail
+                    .compare_exchange(tail, node, Release, Relaxed, &guard);
+                return;
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        self.pop_with(&epoch::pin())
+    }
+
+    fn pop_with(&self, guard: &Guard) -> Option<T> {
+        loop {
+            let head = self.head.load(Acquire, guard);
+            // Safety: `head` is never null and is protected by `guard`
+            let next = unsafe { head.deref() }.next.load(Acquire, guard);
+            let next_node = unsafe { next.as_ref() }?;
+            if self
+                .head
+                .compare_exchange(head, next, Release, Relaxed, guard)
+                .is_ok()
+            {
+                // Never let `tail` point at the node about to be freed
+                let tail = self.tail.load(Relaxed, guard);
+                if head == tail {
+                    let _ = self
+                        .tail
+                        .compare_exchange(tail, next, Release, Relaxed, guard);
+                }
+                // Safety: `next` becomes the new sentinel, its value is read exactly once by this thread
+                unsafe {
+                    guard.defer_destroy(head);
+                    return Some(next_node.value.assume_init_read());
+                }
+            }
+        }
+    }
+
+    // A snapshot, another thread may push or pop right after
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        let head = self.head.load(Acquire, &guard);
+        unsafe { head.deref() }.next.load(Acquire, &guard).is_null()
+    }
+}
+
+impl<T> Default for LockFreeQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for LockFreeQueue<T> {
+    fn drop(&mut self) {
+        // Safety: `&mut self` means no other thread can be using the queue
+        unsafe {
+            let guard = epoch::unprotected();
+            while self.pop_with(guard).is_some() {}
+            drop(self.head.load(Relaxed, guard).into_owned());
+        }
+    }
+}
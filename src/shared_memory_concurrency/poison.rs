@@ -0,0 +1,218 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{LockResult, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use super::instrumented_mutex::{InstrumentedMutex, InstrumentedMutexGuard};
+
+// What to do when a lock is poisoned, i.e. a thread panicked while holding it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoisonPolicy {
+    // Fail with `LockPoisoned` and leave the lock poisoned, for state that may be half-updated
+    #[default]
+    Propagate,
+    // Keep the value as the panicking thread left it and clear the poison flag,
+    // for updates that cannot leave the value inconsistent (a single `+=`, a push)
+    Recover,
+    // Replace the value with `Default::default()` and clear the poison flag,
+    // for state that is cheap to rebuild, such as caches
+    ResetToDefault,
+}
+
+// Returned under `PoisonPolicy::Propagate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockPoisoned;
+
+impl fmt::Display for LockPoisoned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lock poisoned: a thread panicked while holding it")
+    }
+}
+
+impl std::error::Error for LockPoisoned {}
+
+// A mutex-like lock that reports poisoning the way `std::sync::Mutex` does
+pub trait PoisonableLock {
+    type Target;
+    type Guard<'a>: DerefMut<Target = Self::Target>
+    where
+        Self: 'a;
+
+    fn lock_raw(&self) -> LockResult<Self::Guard<'_>>;
+
+    fn clear_poison(&self);
+}
+
+impl<T> PoisonableLock for Mutex<T> {
+    type Target = T;
+    type Guard<'a>
+        = MutexGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock_raw(&self) -> LockResult<MutexGuard<'_, T>> {
+        self.lock()
+    }
+
+    fn clear_poison(&self) {
+        Mutex::clear_poison(self);
+    }
+}
+
+impl<T> PoisonableLock for InstrumentedMutex<T> {
+    type Target = T;
+    type Guard<'a>
+        = InstrumentedMutexGuard<'a, T>
+    where
+        T: 'a;
+
+    #[track_caller]
+    fn lock_raw(&self) -> LockResult<InstrumentedMutexGuard<'_, T>> {
+        self.lock()
+    }
+
+    fn clear_poison(&self) {
+        InstrumentedMutex::clear_poison(self);
+    }
+}
+
+// As a `PoisonableLock`, an `RwLock` is locked for writing; `PoisonableReadLock` adds the shared side
+impl<T> PoisonableLock for RwLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock_raw(&self) -> LockResult<RwLockWriteGuard<'_, T>> {
+        self.write()
+    }
+
+    fn clear_poison(&self) {
+        RwLock::clear_poison(self);
+    }
+}
+
+// A lock that can also be taken shared, such as `std::sync::RwLock`
+pub trait PoisonableReadLock: PoisonableLock {
+    type ReadGuard<'a>: Deref<Target = Self::Target>
+    where
+        Self: 'a;
+
+    fn read_raw(&self) -> LockResult<Self::ReadGuard<'_>>;
+}
+
+impl<T> PoisonableReadLock for RwLock<T> {
+    type ReadGuard<'a>
+        = RwLockReadGuard<'a, T>
+    where
+        T: 'a;
+
+    fn read_raw(&self) -> LockResult<RwLockReadGuard<'_, T>> {
+        self.read()
+    }
+}
+
+// Lock with an explicit poison policy instead of `lock().unwrap()`
+pub trait PoisonPolicyExt: PoisonableLock {
+    #[track_caller]
+    fn lock_or_propagate(&self) -> Result<Self::Guard<'_>, LockPoisoned> {
+        self.lock_raw().map_err(|_| LockPoisoned)
+    }
+
+    #[track_caller]
+    fn lock_or_recover(&self) -> Self::Guard<'_> {
+        self.lock_raw().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    #[track_caller]
+    fn lock_or_reset(&self) -> Self::Guard<'_>
+    where
+        Self::Target: Default,
+    {
+        self.lock_raw().unwrap_or_else(|poisoned| {
+            let mut guard = poisoned.into_inner();
+            *guard = Default::default();
+            self.clear_poison();
+            guard
+        })
+    }
+
+    // Only `Propagate` can fail. `ResetToDefault` needs a `Default` value and panics here;
+    // use `lock_with_reset` for locks whose policy may be a reset
+    #[track_caller]
+    fn lock_with(&self, policy: PoisonPolicy) -> Result<Self::Guard<'_>, LockPoisoned> {
+        match policy {
+            PoisonPolicy::Propagate => self.lock_or_propagate(),
+            PoisonPolicy::Recover => Ok(self.lock_or_recover()),
+            PoisonPolicy::ResetToDefault => panic!("ResetToDefault requires lock_with_reset"),
+        }
+    }
+
+    // `lock_with` for values that can be reset, accepting every policy
+    #[track_caller]
+    fn lock_with_reset(&self, policy: PoisonPolicy) -> Result<Self::Guard<'_>, LockPoisoned>
+    where
+        Self::Target: Default,
+    {
+        match policy {
+            PoisonPolicy::ResetToDefault => Ok(self.lock_or_reset()),
+            policy => self.lock_with(policy),
+        }
+    }
+}
+
+impl<L: PoisonableLock + ?Sized> PoisonPolicyExt for L {}
+
+// Shared-lock counterparts of `PoisonPolicyExt`
+pub trait PoisonPolicyReadExt: PoisonableReadLock {
+    fn read_or_propagate(&self) -> Result<Self::ReadGuard<'_>, LockPoisoned> {
+        self.read_raw().map_err(|_| LockPoisoned)
+    }
+
+    fn read_or_recover(&self) -> Self::ReadGuard<'_> {
+        self.read_raw().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    // A shared guard cannot reset the value, so the reset happens under the exclusive lock first
+    fn read_or_reset(&self) -> Self::ReadGuard<'_>
+    where
+        Self::Target: Default,
+    {
+        loop {
+            match self.read_raw() {
+                Ok(guard) => return guard,
+                Err(poisoned) => {
+                    drop(poisoned);
+                    drop(self.lock_or_reset());
+                }
+            }
+        }
+    }
+
+    // Only `Propagate` can fail; `ResetToDefault` panics here as in `lock_with`
+    fn read_with(&self, policy: PoisonPolicy) -> Result<Self::ReadGuard<'_>, LockPoisoned> {
+        match policy {
+            PoisonPolicy::Propagate => self.read_or_propagate(),
+            PoisonPolicy::Recover => Ok(self.read_or_recover()),
+            PoisonPolicy::ResetToDefault => panic!("ResetToDefault requires read_with_reset"),
+        }
+    }
+
+    fn read_with_reset(&self, policy: PoisonPolicy) -> Result<Self::ReadGuard<'_>, LockPoisoned>
+    where
+        Self::Target: Default,
+    {
+        match policy {
+            PoisonPolicy::ResetToDefault => Ok(self.read_or_reset()),
+            policy => self.read_with(policy),
+        }
+    }
+}
+
+impl<L: PoisonableReadLock + ?Sized> PoisonPolicyReadExt for L {}
@@ -0,0 +1,239 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+
+use super::poison::PoisonPolicyExt;
+
+// One published version of the value
+struct Snapshot<T> {
+    version: u64,
+    value: Arc<T>,
+}
+
+// Read-mostly shared value, e.g. service configuration. Readers never block: `load` clones the `Arc`
+// of the current snapshot under an epoch guard. Writers build a whole new value and swap it in, and
+// the old snapshot is freed once no reader can still be looking at it. Readers holding an older `Arc`
+// keep a consistent view until they drop it.
+pub struct SnapshotCell<T> {
+    current: Atomic<Snapshot<T>>,
+    // Serialises writers so read-modify-write updates are not lost, readers never take it
+    writer: Mutex<()>,
+}
+
+impl<T: Send + Sync> SnapshotCell<T> {
+    pub fn new(value: T) -> Self {
+        SnapshotCell {
+            current: Atomic::new(Snapshot {
+                version: 0,
+                value: Arc::new(value),
+            }),
+            writer: Mutex::new(()),
+        }
+    }
+
+    pub fn load(&self) -> Arc<T> {
+        self.load_versioned().1
+    }
+
+    // Version counts publishes, starting at 0 for the initial value
+    pub fn load_versioned(&self) -> (u64, Arc<T>) {
+        let guard = epoch::pin();
+        // Safety: the pointer is never null and the snapshot is not freed while `guard` is pinned
+        let snapshot = unsafe { self.current.load(Ordering::Acquire, &guard).deref() };
+        (snapshot.version, Arc::clone(&snapshot.value))
+    }
+
+    pub fn version(&self) -> u64 {
+        self.load_versioned().0
+    }
+
+    // Publish `value` as the new snapshot and return its version
+    pub fn store(&self, value: T) -> u64 {
+        let _writer = self.writer.lock_or_recover();
+        self.publish(Arc::new(value))
+    }
+
+    // Publish `f(current)`; concurrent updates are applied one after another, never lost
+    pub fn update(&self, f: impl FnOnce(&T) -> T) -> u64 {
+        let _writer = self.writer.lock_or_recover();
+        let next = f(&self.load());
+        self.publish(Arc::new(next))
+    }
+
+    fn publish(&self, value: Arc<T>) -> u64 {
+        let guard = epoch::pin();
+        // Safety: the pointer is never null, and the snapshot it points to can only be retired by
+        // another publish, which the caller's `writer` lock rules out
+        let version = unsafe { self.current.load(Ordering::Relaxed, &guard).deref() }.version + 1;
+        let previous = self.current.swap(
+            Owned::new(Snapshot { version, value }),
+            Ordering::AcqRel,
+            &guard,
+        );
+        // Safety: the previous snapshot is unreachable from now on, readers already holding it are pinned
+        unsafe { guard.defer_destroy(previous) };
+        version
+    }
+}
+
+impl<T> Drop for SnapshotCell<T> {
+    fn drop(&mut self) {
+        // Safety: `&mut self` means no reader is left
+        unsafe {
+            drop(
+                self.current
+                    .load(Ordering::Relaxed, epoch::unprotected())
+                    .into_owned(),
+            )
+        };
+    }
+}
+
+impl<T: fmt::Debug + Send + Sync> fmt::Debug for SnapshotCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (version, value) = self.load_versioned();
+        f.debug_struct("SnapshotCell")
+            .field("version", &version)
+            .field("value", &value)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub enum ReloadError {
+    Io(io::Error),
+    Parse(String), // The parser's message, the previous snapshot stays current
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReloadError::Io(err) => write!(f, "failed to read config file: {}", err),
+            ReloadError::Parse(message) => write!(f, "failed to parse config file: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
+impl From<io::Error> for ReloadError {
+    fn from(err: io::Error) -> Self {
+        ReloadError::Io(err)
+    }
+}
+
+// Read and parse `path`, publishing the result into `cell`. Returns the new version;
+// on any error nothing is published.
+pub fn reload_from_file<T, E>(
+    cell: &SnapshotCell<T>,
+    path: impl AsRef<Path>,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<u64, ReloadError>
+where
+    T: Send + Sync,
+    E: fmt::Display,
+{
+    let contents = fs::read_to_string(path)?;
+    publish_parsed(cell, &contents, parse)
+}
+
+fn publish_parsed<T, E>(
+    cell: &SnapshotCell<T>,
+    contents: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<u64, ReloadError>
+where
+    T: Send + Sync,
+    E: fmt::Display,
+{
+    let value = parse(contents).map_err(|err| ReloadError::Parse(err.to_string()))?;
+    Ok(cell.store(value))
+}
+
+// Background thread polling a file and reloading it into a `SnapshotCell` when its contents change.
+// Every poll reads the whole file and compares a hash of it, since modification time and length can
+// miss a same-length rewrite within the filesystem's timestamp granularity; meant for small files.
+// A poll can still catch a file half-written: if that fails to parse it is reported, and the
+// finished write changes the contents again and is reloaded. Replacing the file atomically
+// (write a temporary file, then rename it over) avoids publishing a truncated but parseable file.
+// Stops when dropped.
+pub struct FileReloader {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    path: PathBuf,
+}
+
+impl FileReloader {
+    // `on_reload` is told about every reload attempt, including failed ones
+    pub fn spawn<T, E>(
+        cell: Arc<SnapshotCell<T>>,
+        path: impl Into<PathBuf>,
+        poll_interval: Duration,
+        parse: impl Fn(&str) -> Result<T, E> + Send + 'static,
+        on_reload: impl Fn(Result<u64, ReloadError>) + Send + 'static,
+    ) -> Self
+    where
+        T: Send + Sync + 'static,
+        E: fmt::Display,
+    {
+        let path = path.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        // Taken before the thread starts so a write right after `spawn` returns is not missed.
+        // The file as it is now is assumed to be what `cell` was built from.
+        let mut last_seen = fs::read_to_string(&path)
+            .ok()
+            .map(|contents| hash(&contents));
+        let handle = {
+            let (path, stop) = (path.clone(), Arc::clone(&stop));
+            thread::spawn(move || {
+                while !stop.load(Ordering::Acquire) {
+                    thread::park_timeout(poll_interval);
+                    // A missing or unreadable file is skipped until it can be read again
+                    let Ok(contents) = fs::read_to_string(&path) else {
+                        continue;
+                    };
+                    let current = hash(&contents);
+                    // Failed attempts count as seen too, so a broken file is reported once
+                    if last_seen != Some(current) {
+                        last_seen = Some(current);
+                        on_reload(publish_parsed(&cell, &contents, &parse));
+                    }
+                }
+            })
+        };
+        FileReloader {
+            stop,
+            handle: Some(handle),
+            path,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileReloader {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+fn hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
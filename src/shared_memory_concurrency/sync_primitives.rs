@@ -0,0 +1,264 @@
+use std::fmt;
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+use super::poison::PoisonPolicyExt;
+
+// Every primitive here keeps a plain counter under its Mutex and updates it in one step, so a
+// panicking thread cannot leave it inconsistent and poisoning is recovered rather than propagated.
+
+// Returned by the `*_timeout` variants when the deadline passes first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTimedOut {
+    pub timeout: Duration,
+}
+
+impl fmt::Display for WaitTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {:?}", self.timeout)
+    }
+}
+
+impl std::error::Error for WaitTimedOut {}
+
+// One-shot gate: waiters block until `count_down` has been called `count` times
+#[derive(Debug)]
+pub struct CountdownLatch {
+    remaining: Mutex<usize>,
+    released: Condvar,
+}
+
+impl CountdownLatch {
+    pub fn new(count: usize) -> Self {
+        CountdownLatch {
+            remaining: Mutex::new(count),
+            released: Condvar::new(),
+        }
+    }
+
+    // Extra calls after the latch opened are ignored
+    pub fn count_down(&self) {
+        let mut remaining = self.remaining.lock_or_recover();
+        if *remaining > 0 {
+            *remaining -= 1;
+            if *remaining == 0 {
+                self.released.notify_all();
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        *self.remaining.lock_or_recover()
+    }
+
+    pub fn wait(&self) {
+        let remaining = self.remaining.lock_or_recover();
+        let _open = self
+            .released
+            .wait_while(remaining, |remaining| *remaining > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<(), WaitTimedOut> {
+        let remaining = self.remaining.lock_or_recover();
+        let (remaining, _) = self
+            .released
+            .wait_timeout_while(remaining, timeout, |remaining| *remaining > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        if *remaining == 0 {
+            Ok(())
+        } else {
+            Err(WaitTimedOut { timeout })
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BarrierState {
+    waiting: usize,
+    generation: u64, // Bumped every time the barrier trips
+}
+
+// What a thread learns when it passes a `CyclicBarrier`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult {
+    pub is_leader: bool, // The last thread to arrive, which ran the barrier action
+    pub generation: u64, // Which trip of the barrier this was, starting at 0
+}
+
+// Reusable barrier for a fixed number of threads. The last thread to arrive runs the optional
+// action before anyone is released, so the action sees every thread's work from the finished phase.
+pub struct CyclicBarrier {
+    parties: usize,
+    state: Mutex<BarrierState>,
+    tripped: Condvar,
+    action: Option<Box<dyn Fn(u64) + Send + Sync>>,
+}
+
+impl CyclicBarrier {
+    pub fn new(parties: usize) -> Self {
+        CyclicBarrier {
+            parties: parties.max(1),
+            state: Mutex::new(BarrierState {
+                waiting: 0,
+                generation: 0,
+            }),
+            tripped: Condvar::new(),
+            action: None,
+        }
+    }
+
+    // `action` receives the generation that just completed
+    pub fn with_action(parties: usize, action: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        CyclicBarrier {
+            action: Some(Box::new(action)),
+            ..Self::new(parties)
+        }
+    }
+
+    pub fn parties(&self) -> usize {
+        self.parties
+    }
+
+    pub fn wait(&self) -> BarrierWaitResult {
+        self.wait_inner(None)
+            .expect("waiting without a timeout cannot time out")
+    }
+
+    // On timeout the thread withdraws, so the barrier still needs `parties` arrivals to trip
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<BarrierWaitResult, WaitTimedOut> {
+        self.wait_inner(Some(timeout))
+    }
+
+    fn wait_inner(&self, timeout: Option<Duration>) -> Result<BarrierWaitResult, WaitTimedOut> {
+        let mut state = self.state.lock_or_recover();
+        let generation = state.generation;
+        state.waiting += 1;
+
+        if state.waiting == self.parties {
+            // Advance before running the action and wake the others from a drop guard,
+            // so a panicking action still releases them
+            state.waiting = 0;
+            state.generation += 1;
+            let _wake = NotifyOnDrop(&self.tripped);
+            if let Some(action) = &self.action {
+                action(generation);
+            }
+            return Ok(BarrierWaitResult {
+                is_leader: true,
+                generation,
+            });
+        }
+
+        let still_waiting = |state: &mut BarrierState| state.generation == generation;
+        let mut state = match timeout {
+            None => self
+                .tripped
+                .wait_while(state, still_waiting)
+                .unwrap_or_else(PoisonError::into_inner),
+            Some(timeout) => {
+                self.tripped
+                    .wait_timeout_while(state, timeout, still_waiting)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+        };
+        if state.generation == generation {
+            state.waiting -= 1;
+            return Err(WaitTimedOut {
+                timeout: timeout.unwrap_or_default(),
+            });
+        }
+        Ok(BarrierWaitResult {
+            is_leader: false,
+            generation,
+        })
+    }
+}
+
+// Wakes every waiter on drop, including when unwinding
+struct NotifyOnDrop<'a>(&'a Condvar);
+
+impl Drop for NotifyOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.notify_all();
+    }
+}
+
+impl fmt::Debug for CyclicBarrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CyclicBarrier")
+            .field("parties", &self.parties)
+            .field("state", &self.state)
+            .field("has_action", &self.action.is_some())
+            .finish()
+    }
+}
+
+// Counting semaphore; permits are handed out as guards that give the permit back when dropped
+#[derive(Debug)]
+pub struct Semaphore {
+    permits: Mutex<usize>,
+    available: Condvar,
+}
+
+impl Semaphore {
+    pub fn new(permits: usize) -> Self {
+        Semaphore {
+            permits: Mutex::new(permits),
+            available: Condvar::new(),
+        }
+    }
+
+    pub fn available_permits(&self) -> usize {
+        *self.permits.lock_or_recover()
+    }
+
+    pub fn acquire(&self) -> SemaphorePermit<'_> {
+        let permits = self.permits.lock_or_recover();
+        let mut permits = self
+            .available
+            .wait_while(permits, |permits| *permits == 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        *permits -= 1;
+        SemaphorePermit { semaphore: self }
+    }
+
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        let mut permits = self.permits.lock_or_recover();
+        if *permits == 0 {
+            return None;
+        }
+        *permits -= 1;
+        Some(SemaphorePermit { semaphore: self })
+    }
+
+    pub fn acquire_timeout(&self, timeout: Duration) -> Result<SemaphorePermit<'_>, WaitTimedOut> {
+        let permits = self.permits.lock_or_recover();
+        let (mut permits, _) = self
+            .available
+            .wait_timeout_while(permits, timeout, |permits| *permits == 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        if *permits == 0 {
+            return Err(WaitTimedOut { timeout });
+        }
+        *permits -= 1;
+        Ok(SemaphorePermit { semaphore: self })
+    }
+
+    fn release(&self) {
+        *self.permits.lock_or_recover() += 1;
+        self.available.notify_one();
+    }
+}
+
+#[derive(Debug)]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
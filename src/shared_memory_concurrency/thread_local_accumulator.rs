@@ -0,0 +1,282 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicI64, AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+use super::counter::SharedCounter;
+
+// A value that can be accumulated in a per-thread atomic slot. Each slot has exactly one writer,
+// its owning thread, so `add` is a plain load and store rather than a locked read-modify-write.
+pub trait Accumulate: Copy + Send + Sync + 'static {
+    type Slot: Default + Send + Sync;
+
+    const ZERO: Self;
+
+    // Only ever called by the slot's owning thread
+    fn add(slot: &Self::Slot, value: Self);
+
+    fn load(slot: &Self::Slot) -> Self;
+
+    fn combine(self, other: Self) -> Self;
+}
+
+impl Accumulate for u64 {
+    type Slot = AtomicU64;
+
+    const ZERO: Self = 0;
+
+    fn add(slot: &AtomicU64, value: u64) {
+        slot.store(
+            slot.load(Ordering::Relaxed).wrapping_add(value),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn load(slot: &AtomicU64) -> u64 {
+        slot.load(Ordering::Relaxed)
+    }
+
+    fn combine(self, other: u64) -> u64 {
+        self.wrapping_add(other)
+    }
+}
+
+impl Accumulate for i64 {
+    type Slot = AtomicI64;
+
+    const ZERO: Self = 0;
+
+    fn add(slot: &AtomicI64, value: i64) {
+        slot.store(
+            slot.load(Ordering::Relaxed).wrapping_add(value),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn load(slot: &AtomicI64) -> i64 {
+        slot.load(Ordering::Relaxed)
+    }
+
+    fn combine(self, other: i64) -> i64 {
+        self.wrapping_add(other)
+    }
+}
+
+impl Accumulate for usize {
+    type Slot = AtomicUsize;
+
+    const ZERO: Self = 0;
+
+    fn add(slot: &AtomicUsize, value: usize) {
+        slot.store(
+            slot.load(Ordering::Relaxed).wrapping_add(value),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn load(slot: &AtomicUsize) -> usize {
+        slot.load(Ordering::Relaxed)
+    }
+
+    fn combine(self, other: usize) -> usize {
+        self.wrapping_add(other)
+    }
+}
+
+// Stored as its bit pattern, merges add the per-thread partial sums in slot order
+impl Accumulate for f64 {
+    type Slot = AtomicU64;
+
+    const ZERO: Self = 0.0;
+
+    fn add(slot: &AtomicU64, value: f64) {
+        let current = f64::from_bits(slot.load(Ordering::Relaxed));
+        slot.store((current + value).to_bits(), Ordering::Relaxed);
+    }
+
+    fn load(slot: &AtomicU64) -> f64 {
+        f64::from_bits(slot.load(Ordering::Relaxed))
+    }
+
+    fn combine(self, other: f64) -> f64 {
+        self + other
+    }
+}
+
+struct SlotNode<T: Accumulate> {
+    slot: T::Slot,
+    next: *mut SlotNode<T>,
+}
+
+// The current thread's slot in one accumulator
+struct CachedSlot {
+    alive: Weak<()>, // Dead once the accumulator is dropped
+    node: *const (),
+}
+
+// Slot of the current thread in each accumulator it has touched, keyed by accumulator id.
+// Ids are never reused, so an entry left behind by a dropped accumulator is never looked up again;
+// such entries are pruned once the cache has doubled since the last prune, keeping it proportional
+// to the accumulators still alive.
+#[derive(Default)]
+struct SlotCache {
+    slots: HashMap<usize, CachedSlot>,
+    prune_at: usize,
+}
+
+impl SlotCache {
+    fn insert(&mut self, id: usize, slot: CachedSlot) {
+        if self.slots.len() >= self.prune_at {
+            self.slots.retain(|_, slot| slot.alive.strong_count() > 0);
+            self.prune_at = (2 * self.slots.len()).max(16);
+        }
+        self.slots.insert(id, slot);
+    }
+}
+
+thread_local! {
+    static SLOTS: RefCell<SlotCache> = RefCell::new(SlotCache::default());
+}
+
+// Number of accumulator slots cached by the current thread, including not yet pruned dead ones
+pub fn cached_slot_count() -> usize {
+    SLOTS
+        .try_with(|cache| cache.borrow().slots.len())
+        .unwrap_or(0)
+}
+
+// Sum updated by many threads without any shared write: each thread adds into its own slot,
+// and `merge` adds up all slots on demand.
+//
+// Guarantees: `merge` never blocks writers or other merges. Every slot is read atomically, so a merge
+// taken while writers run sees each thread's updates up to some point, and for non-negative values
+// lies between the totals before and after the merge. Once writers are done (e.g. joined) it is exact.
+//
+// Each thread caches its slot in a thread-local. While that thread-local is being torn down
+// (e.g. `add` called from another thread-local's destructor) there is no cache, and every such
+// `add` goes to a fresh slot: still counted by `merge`, at the cost of one allocation per call.
+pub struct ThreadLocalAccumulator<T: Accumulate> {
+    id: usize,
+    alive: Arc<()>, // Lets thread caches tell that this accumulator is gone
+    // Append-only list of slots, one per thread that has called `add`, freed on drop
+    head: AtomicPtr<SlotNode<T>>,
+    _slots: PhantomData<Box<SlotNode<T>>>,
+}
+
+// Slots are `Send + Sync` atomics, the raw list pointers are only dereferenced as described above
+unsafe impl<T: Accumulate> Send for ThreadLocalAccumulator<T> {}
+unsafe impl<T: Accumulate> Sync for ThreadLocalAccumulator<T> {}
+
+impl<T: Accumulate> ThreadLocalAccumulator<T> {
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        ThreadLocalAccumulator {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            alive: Arc::new(()),
+            head: AtomicPtr::new(ptr::null_mut()),
+            _slots: PhantomData,
+        }
+    }
+
+    pub fn add(&self, value: T) {
+        T::add(self.local_slot(), value);
+    }
+
+    // Combine every thread's slot, see the type's guarantees above
+    pub fn merge(&self) -> T {
+        self.slots()
+            .fold(T::ZERO, |total, slot| total.combine(T::load(slot)))
+    }
+
+    // Number of threads that have added to this accumulator
+    pub fn thread_count(&self) -> usize {
+        self.slots().count()
+    }
+
+    fn slots(&self) -> impl Iterator<Item = &T::Slot> {
+        let mut node = self.head.load(Ordering::Acquire);
+        std::iter::from_fn(move || {
+            // Safety: nodes are only freed when the accumulator is dropped, which `&self` rules out
+            let current = unsafe { node.as_ref() }?;
+            node = current.next;
+            Some(&current.slot)
+        })
+    }
+
+    fn local_slot(&self) -> &T::Slot {
+        let cached = SLOTS
+            .try_with(|cache| cache.borrow().slots.get(&self.id).map(|slot| slot.node))
+            .ok()
+            .flatten();
+        let node = match cached {
+            Some(node) => node as *const SlotNode<T>,
+            None => {
+                let node = self.register();
+                // Not cached during thread-local teardown, see the type's docs
+                let _ = SLOTS.try_with(|cache| {
+                    cache.borrow_mut().insert(
+                        self.id,
+                        CachedSlot {
+                            alive: Arc::downgrade(&self.alive),
+                            node: node as *const (),
+                        },
+                    )
+                });
+                node
+            }
+        };
+        // Safety: the node belongs to this accumulator (ids are unique) and lives as long as it does
+        unsafe { &(*node).slot }
+    }
+
+    // Push a fresh slot for the current thread onto the list
+    fn register(&self) -> *const SlotNode<T> {
+        let node = Box::into_raw(Box::new(SlotNode {
+            slot: T::Slot::default(),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // Safety: `node` is not published until the exchange succeeds
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return node,
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+impl<T: Accumulate> Default for ThreadLocalAccumulator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Accumulate> Drop for ThreadLocalAccumulator<T> {
+    fn drop(&mut self) {
+        // Other threads' entries die with `alive` and are pruned by those threads
+        let _ = SLOTS.try_with(|cache| cache.borrow_mut().slots.remove(&self.id));
+        let mut node = *self.head.get_mut();
+        while !node.is_null() {
+            // Safety: every node came from `Box::into_raw` in `register` and is freed exactly once
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next;
+        }
+    }
+}
+
+impl SharedCounter for ThreadLocalAccumulator<u64> {
+    fn add(&self, delta: u64) {
+        ThreadLocalAccumulator::add(self, delta);
+    }
+
+    fn get(&self) -> u64 {
+        self.merge()
+    }
+}
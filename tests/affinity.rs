@@ -0,0 +1,81 @@
+use be_rust_master::multi_thread_processor::affinity::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11\n"),
+            Some(vec![0, 1, 2, 3, 8, 10, 11])
+        );
+        assert_eq!(parse_cpu_list("5"), Some(vec![5]));
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("a-3"), None);
+    }
+
+    #[test]
+    fn test_topology_contains_every_node() {
+        let topology = numa_topology();
+        assert!(!topology.is_empty());
+        assert_eq!(node_of_core(&[vec![0, 1], vec![2, 3]], 3), Some(1));
+        assert_eq!(node_of_core(&[vec![0, 1]], 7), None);
+    }
+
+    #[test]
+    fn test_unpinned_workers_report_placement() {
+        let (result, report) = numa_sum_of_squares(1000, 4, &Pinning::None, |i| i as u64);
+        assert_eq!(result.value, (0..1000u64).map(|x| x * x).sum::<u64>());
+        assert_eq!(report.workers.len(), 4);
+        assert!(report
+            .workers
+            .iter()
+            .all(|w| w.requested_core.is_none() && !w.pinned));
+        assert_eq!(report.to_string().lines().count(), 5);
+    }
+
+    #[test]
+    fn test_pinned_workers_run_on_requested_core() {
+        // Only cores in our affinity mask can be pinned to, and core 0 need not be one of them
+        let Ok(allowed) = allowed_cores() else {
+            return;
+        };
+        let core = *allowed.last().unwrap();
+        let (workers, report) = run_pinned(3, &Pinning::Cores(vec![core]), |worker| worker * 10);
+        assert_eq!(workers, vec![0, 10, 20]);
+        for placement in &report.workers {
+            assert_eq!(placement.requested_core, Some(core));
+            assert!(placement.pinned, "{:?}", placement.pin_error);
+            assert_eq!(placement.observed_core, Some(core));
+        }
+
+        let (_, report) = run_pinned(2, &Pinning::SpreadNodes, |_| ());
+        for placement in &report.workers {
+            assert!(allowed.contains(&placement.requested_core.unwrap()));
+            assert!(placement.pinned);
+        }
+    }
+
+    #[test]
+    fn test_topology_only_lists_allowed_cores() {
+        let Ok(allowed) = allowed_cores() else {
+            return;
+        };
+        assert!(!allowed.is_empty());
+        for cpu in numa_topology().concat() {
+            assert!(allowed.contains(&cpu));
+        }
+    }
+
+    #[test]
+    fn test_failed_pin_is_reported() {
+        let (_, report) = run_pinned(1, &Pinning::Cores(vec![1 << 20]), |_| ());
+        let placement = &report.workers[0];
+        assert!(!placement.pinned);
+        assert!(placement.pin_error.is_some());
+        assert!(report
+            .to_string()
+            .contains(placement.pin_error.as_deref().unwrap()));
+    }
+}
@@ -0,0 +1,152 @@
+use be_rust_master::multi_thread_processor::aggregations::*;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_counts_every_value_once() {
+        let data: Vec<f64> = (0..10_000)
+            .map(|i| (i % 130) as f64 - 10.0)
+            .chain([f64::NAN])
+            .collect();
+        let edges = [0.0, 10.0, 50.0, 100.0];
+        for threads in [1, 3, 8] {
+            let histogram = parallel_histogram(&data, &edges, threads).unwrap();
+            assert_eq!(histogram.total(), data.len() as u64);
+            assert_eq!(histogram.unordered, 1);
+
+            let in_bin = |lo: f64, hi: f64, last: bool| {
+                data.iter()
+                    .filter(|&&x| x >= lo && (x < hi || (last && x == hi)))
+                    .count() as u64
+            };
+            assert_eq!(
+                histogram.counts,
+                vec![
+                    in_bin(0.0, 10.0, false),
+                    in_bin(10.0, 50.0, false),
+                    in_bin(50.0, 100.0, true)
+                ]
+            );
+            assert_eq!(
+                histogram.below,
+                data.iter().filter(|&&x| x < 0.0).count() as u64
+            );
+            assert_eq!(
+                histogram.above,
+                data.iter().filter(|&&x| x > 100.0).count() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_histogram_rejects_bad_edges() {
+        assert_eq!(
+            parallel_histogram(&[1u32], &[3], 2),
+            Err(HistogramError::TooFewEdges)
+        );
+        assert_eq!(
+            parallel_histogram(&[1u32], &[0, 5, 5, 9], 2),
+            Err(HistogramError::EdgesNotIncreasing { index: 2 })
+        );
+        assert_eq!(
+            parallel_histogram(&[1.0], &[0.0, f64::NAN], 2),
+            Err(HistogramError::EdgesNotIncreasing { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_frequencies_are_exact() {
+        let data: Vec<String> = (0..5000).map(|i| format!("key{}", i % 37)).collect();
+        let mut expected: HashMap<String, usize> = HashMap::new();
+        for value in &data {
+            *expected.entry(value.clone()).or_insert(0) += 1;
+        }
+        for threads in [1, 4, 16] {
+            assert_eq!(parallel_frequencies(&data, threads), expected);
+        }
+        assert!(parallel_frequencies::<u8>(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_top_k_finds_heavy_hitters() {
+        // Values 0..5 are heavy hitters among many rare values
+        let data: Vec<u32> = (0..100_000u32)
+            .map(|i| {
+                if i % 2 == 0 {
+                    i % 10 / 2
+                } else {
+                    1000 + i % 20_000
+                }
+            })
+            .collect();
+        let exact = parallel_frequencies(&data, 4);
+        let top = parallel_top_k(&data, 5, 4);
+
+        let mut values: Vec<u32> = top.iter().map(|entry| entry.value).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+        for entry in &top {
+            let true_count = exact[&entry.value];
+            assert!(entry.count >= true_count);
+            assert!(entry.count - entry.error <= true_count);
+        }
+    }
+
+    #[test]
+    fn test_space_saving_is_exact_below_capacity() {
+        let mut summary = SpaceSaving::new(10);
+        for value in [3, 1, 3, 2, 3, 1] {
+            summary.insert(&value);
+        }
+        let top = summary.top(2);
+        assert_eq!(
+            top[0],
+            TopKEntry {
+                value: 3,
+                count: 3,
+                error: 0
+            }
+        );
+        assert_eq!(
+            top[1],
+            TopKEntry {
+                value: 1,
+                count: 2,
+                error: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_space_saving_eviction_keeps_invariants() {
+        // 200 distinct values through 16 counters; values below 4 make up half the stream
+        let data: Vec<u64> = (0..4_000u64)
+            .map(|i| {
+                if i % 2 == 0 {
+                    i % 8 / 2
+                } else {
+                    i * 7919 % 200
+                }
+            })
+            .collect();
+        let mut summary = SpaceSaving::new(16);
+        for value in &data {
+            summary.insert(value);
+        }
+        let exact = parallel_frequencies(&data, 1);
+        let top = summary.top(16);
+        assert_eq!(top.len(), 16);
+        // Every insert adds exactly one to some counter, evictions included
+        assert_eq!(top.iter().map(|e| e.count).sum::<usize>(), data.len());
+        for entry in &top {
+            let true_count = exact[&entry.value];
+            assert!(entry.count - entry.error <= true_count && true_count <= entry.count);
+        }
+        let mut heavy: Vec<u64> = top.iter().take(4).map(|e| e.value).collect();
+        heavy.sort();
+        assert_eq!(heavy, vec![0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,58 @@
+use be_rust_master::benchmark::*;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(10));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        let stddev_ms = stats.stddev.as_secs_f64() * 1000.0;
+        assert!((stddev_ms - 5.766).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_single_sample_has_zero_stddev() {
+        let stats = Stats::from_samples(&[Duration::from_micros(7)]);
+        assert_eq!(stats.min, stats.p95);
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_sweep_covers_every_combination() {
+        let config = BenchmarkConfig {
+            warmup_runs: 1,
+            runs: 3,
+            thread_counts: vec![1, 2, 3],
+            input_sizes: vec![1_000, 5_000],
+            strategies: Strategy::ALL.to_vec(),
+        };
+        let results = run_benchmarks(&config);
+        // Per size: one single-threaded row plus one row per thread count for the two parallel strategies
+        assert_eq!(results.len(), 2 * (1 + 2 * 3));
+        for r in results
+            .iter()
+            .filter(|r| r.strategy == Strategy::SingleThread)
+        {
+            assert_eq!(r.threads, 1);
+            assert_eq!(r.speedup, 1.0);
+        }
+        assert!(results.iter().all(|r| r.speedup > 0.0));
+
+        let table = render_table(&results);
+        assert_eq!(table.lines().count(), results.len() + 1);
+        assert!(table.contains("simd_multi_thread"));
+
+        let json = render_json(&results);
+        assert!(
+            json.starts_with("[{\"strategy\":\"single_thread\",\"input_size\":1000,\"threads\":1,")
+        );
+        assert_eq!(json.matches("\"median_ns\"").count(), results.len());
+    }
+}
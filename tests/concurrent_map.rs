@@ -0,0 +1,152 @@
+use be_rust_master::benchmark::render_map_table;
+use be_rust_master::shared_memory_concurrency::concurrent_map::*;
+use be_rust_master::shared_memory_concurrency::poison::PoisonPolicy;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+
+fn check_basic_operations<M: ConcurrentMap<String, u32>>(map: &M) {
+    assert!(map.is_empty());
+    assert_eq!(map.insert("a".to_string(), 1), None);
+    assert_eq!(map.insert("b".to_string(), 2), None);
+    assert_eq!(map.insert("a".to_string(), 10), Some(1));
+    assert_eq!(map.get(&"a".to_string()), Some(10));
+    assert_eq!(
+        map.update(&"b".to_string(), |v| {
+            *v *= 3;
+            *v
+        }),
+        Some(6)
+    );
+    assert_eq!(map.update(&"missing".to_string(), |v| *v), None);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.remove(&"a".to_string()), Some(10));
+    assert_eq!(map.remove(&"a".to_string()), None);
+    assert_eq!(map.get(&"b".to_string()), Some(6));
+    assert_eq!(map.len(), 1);
+}
+
+fn concurrent_updates_are_not_lost<M: ConcurrentMap<u64, u64> + 'static>(map: Arc<M>) {
+    for key in 0..16 {
+        map.insert(key, 0);
+    }
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..1_600u64 {
+                    map.update(&(i % 16), |v| *v += 1);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    for key in 0..16 {
+        assert_eq!(map.get(&key), Some(800));
+    }
+}
+
+// Bump `key` and panic inside the update closure, poisoning the lock it ran under
+fn poison_with_update<M: ConcurrentMap<u64, u64> + 'static>(map: &Arc<M>, key: u64) {
+    let writer = Arc::clone(map);
+    let result = thread::spawn(move || {
+        writer.update(&key, |v| {
+            *v += 1;
+            panic!("update failed halfway");
+        });
+    })
+    .join();
+    assert!(result.is_err());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rwlock_map_operations() {
+        check_basic_operations(&RwLockMap::new());
+    }
+
+    #[test]
+    fn test_sharded_map_operations() {
+        check_basic_operations(&ShardedMap::with_shards(3));
+        check_basic_operations(&ShardedMap::with_shards(0));
+    }
+
+    #[test]
+    fn test_updates_in_place_under_contention() {
+        concurrent_updates_are_not_lost(Arc::new(RwLockMap::new()));
+        concurrent_updates_are_not_lost(Arc::new(ShardedMap::with_shards(4)));
+    }
+
+    #[test]
+    fn test_benchmark_covers_every_ratio_and_backend() {
+        let workload = MapWorkload {
+            threads: 2,
+            operations_per_thread: 2_000,
+            key_space: 100,
+            ..Default::default()
+        };
+        let results = compare_maps(&[0.0, 1.0], &workload);
+        let rows: Vec<(&str, f64)> = results.iter().map(|r| (r.backend, r.read_ratio)).collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("rwlock", 0.0),
+                ("sharded", 0.0),
+                ("rwlock", 1.0),
+                ("sharded", 1.0)
+            ]
+        );
+        assert!(results.iter().all(|r| r.operations == 4_000));
+
+        let table = render_map_table(&results);
+        assert_eq!(table.lines().count(), 5);
+        assert!(table.lines().nth(1).unwrap().starts_with("rwlock"));
+    }
+
+    #[test]
+    fn test_write_only_benchmark_keeps_key_space() {
+        let map = Arc::new(ShardedMap::with_shards(8));
+        let workload = MapWorkload {
+            threads: 4,
+            operations_per_thread: 1_000,
+            read_ratio: 0.0,
+            key_space: 50,
+            seed: 7,
+        };
+        run_map_benchmark("sharded", Arc::clone(&map), &workload);
+        assert_eq!(map.len(), 50);
+        let total: u64 = (0..50).map(|key| map.get(&key).unwrap()).sum();
+        assert_eq!(total, 4_000);
+    }
+
+    #[test]
+    fn test_poisoned_map_follows_policy() {
+        // Recover keeps the value as the panicking closure left it
+        let map = Arc::new(RwLockMap::new().with_policy(PoisonPolicy::Recover));
+        map.insert(1, 10);
+        poison_with_update(&map, 1);
+        assert_eq!(map.get(&1), Some(11));
+        assert_eq!(map.insert(2, 0), None);
+
+        // Reset empties the poisoned shard, here the only one
+        let map = Arc::new(ShardedMap::with_shards(1).with_policy(PoisonPolicy::ResetToDefault));
+        map.insert(1, 10);
+        map.insert(2, 20);
+        poison_with_update(&map, 1);
+        assert_eq!(map.len(), 0);
+        map.insert(3, 30);
+        assert_eq!(map.get(&3), Some(30));
+
+        // Propagate, the default, refuses to touch the poisoned map
+        let map = Arc::new(RwLockMap::new());
+        map.insert(1, 10);
+        poison_with_update(&map, 1);
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| map.get(&1))).is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| map.insert(2, 0))).is_err());
+    }
+}
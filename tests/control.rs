@@ -0,0 +1,94 @@
+use be_rust_master::multi_thread_processor::control::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_reported_per_worker() {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let control = JobControl {
+            progress: Some(Arc::new(move |p| sender.lock().unwrap().send(p).unwrap())),
+            sub_chunk_size: 100,
+            ..JobControl::default()
+        };
+        let data = Arc::new(vec![2u64; 4000]);
+        let result = controlled_sum_of_squares(data, 4, 0, &control).unwrap();
+        drop(control);
+        assert_eq!(result.value, 16_000);
+        assert_eq!(result.threads, 4);
+
+        let reports: Vec<Progress> = receiver.iter().collect();
+        assert_eq!(reports.len(), 40);
+        for worker in 0..4 {
+            let last = reports
+                .iter()
+                .filter(|p| p.worker == worker)
+                .map(|p| p.processed)
+                .max();
+            assert_eq!(last, Some(1000));
+        }
+    }
+
+    #[test]
+    fn test_cancelled_before_start() {
+        let control = JobControl::default();
+        control.cancellation.cancel();
+        let err = controlled_sum_of_squares(Arc::new(vec![1; 1000]), 3, 0, &control).unwrap_err();
+        assert_eq!(err, JobError::Cancelled(Cancelled { processed: 0 }));
+    }
+
+    #[test]
+    fn test_cancel_from_progress_stops_workers() {
+        let token = CancellationToken::new();
+        let observer = token.clone();
+        let control = JobControl {
+            cancellation: token,
+            // Stop the job as soon as any worker is a quarter of the way through
+            progress: Some(Arc::new(move |p: Progress| {
+                if p.processed * 4 >= p.total {
+                    observer.cancel();
+                }
+            })),
+            sub_chunk_size: 10,
+        };
+        let err = controlled_sum_of_squares(Arc::new(vec![1; 100_000]), 2, 0, &control);
+        let JobError::Cancelled(err) = err.unwrap_err() else {
+            panic!("expected the job to be cancelled");
+        };
+        assert!(err.processed > 0 && err.processed < 100_000);
+        assert!(control.cancellation.is_cancelled());
+        assert!(err.to_string().starts_with("job cancelled after"));
+    }
+
+    #[test]
+    fn test_panicking_callback_is_isolated_and_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let control = JobControl {
+            // Worker 1 fails on its first report only, so one retry recovers it
+            progress: Some(Arc::new(move |p: Progress| {
+                if p.worker == 1 && counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("progress sink failed");
+                }
+            })),
+            sub_chunk_size: 100,
+            ..JobControl::default()
+        };
+        let data = Arc::new(vec![3u64; 900]);
+        let err = controlled_sum_of_squares(Arc::clone(&data), 3, 0, &control).unwrap_err();
+        let JobError::Panicked(panic) = err else {
+            panic!("expected a worker panic");
+        };
+        assert_eq!((panic.chunk, panic.range), (1, 300..600));
+        assert_eq!(panic.message, "progress sink failed");
+
+        calls.store(0, Ordering::SeqCst);
+        let result = controlled_sum_of_squares(data, 3, 1, &control).unwrap();
+        assert_eq!(result.value, 900 * 9);
+    }
+}
@@ -0,0 +1,91 @@
+use be_rust_master::error_handling_functions::concurrent_operation;
+use be_rust_master::shared_memory_concurrency::deadlock::*;
+use std::thread;
+
+// Lock `first` then `second` on a named thread and return the outcome
+fn lock_in_order(
+    name: &str,
+    first: &DebugMutex<u32>,
+    second: &DebugMutex<u32>,
+) -> Result<(), DeadlockError> {
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn_scoped(scope, || {
+                let _first = first.lock()?;
+                let _second = second.lock()?;
+                Ok(())
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent_order_is_allowed() {
+        let a = DebugMutex::new("a", 0);
+        let b = DebugMutex::new("b", 0);
+        lock_in_order("first", &a, &b).unwrap();
+        lock_in_order("second", &a, &b).unwrap();
+        *a.lock().unwrap() += 1;
+        assert_eq!(*a.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_inverted_order_reports_cycle() {
+        let a = DebugMutex::new("a", 0);
+        let b = DebugMutex::new("b", 0);
+        lock_in_order("forward", &a, &b).unwrap();
+        let err = lock_in_order("backward", &b, &a).unwrap_err();
+
+        assert_eq!(err.threads(), vec!["backward", "forward"]);
+        assert_eq!(err.cycle.len(), 2);
+        assert_eq!((err.cycle[0].held, err.cycle[0].acquired), ("b", "a"));
+        assert_eq!((err.cycle[1].held, err.cycle[1].acquired), ("a", "b"));
+        assert!(err
+            .cycle
+            .iter()
+            .all(|e| e.acquired_at.contains("deadlock.rs")));
+        assert!(err.to_string().starts_with("potential deadlock"));
+
+        // The refused lock was not taken, so the locks are still usable
+        assert_eq!(*a.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_longer_cycle_through_three_locks() {
+        let a = DebugMutex::new("a", 0);
+        let b = DebugMutex::new("b", 0);
+        let c = DebugMutex::new("c", 0);
+        lock_in_order("t1", &a, &b).unwrap();
+        lock_in_order("t2", &b, &c).unwrap();
+        let err = lock_in_order("t3", &c, &a).unwrap_err();
+        let order: Vec<(&str, &str)> = err.cycle.iter().map(|e| (e.held, e.acquired)).collect();
+        assert_eq!(order, vec![("c", "a"), ("a", "b"), ("b", "c")]);
+        assert_eq!(err.threads(), vec!["t3", "t1", "t2"]);
+    }
+
+    #[test]
+    fn test_relocking_held_lock_is_reported() {
+        let a = DebugMutex::new("a", 0);
+        let _guard = a.lock().unwrap();
+        let err = a.lock().unwrap_err();
+        assert_eq!(err.cycle.len(), 1);
+        assert_eq!((err.cycle[0].held, err.cycle[0].acquired), ("a", "a"));
+    }
+
+    #[test]
+    fn test_concurrent_operation_reports_deadlock() {
+        let err = concurrent_operation().unwrap_err();
+        assert_eq!(err.threads(), vec!["audit", "deposit"]);
+        assert_eq!(
+            (err.cycle[0].held, err.cycle[0].acquired),
+            ("ledger", "accounts")
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use be_rust_master::shared_memory_concurrency::event_log::*;
+use be_rust_master::shared_memory_concurrency::record_shared_memory_concurrency;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_records_every_update_in_order() {
+        let log = record_shared_memory_concurrency(3, 20);
+        assert_eq!(log.workers, 3);
+        assert_eq!(log.events.len(), 60);
+        assert_eq!(log.final_value(), Some(60));
+        for (i, event) in log.events.iter().enumerate() {
+            assert_eq!(event.sequence, i);
+            assert_eq!(event.value, i as i32 + 1);
+        }
+        assert!(log.events.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[test]
+    fn test_each_worker_sees_its_own_updates_increase() {
+        let log = record_shared_memory_concurrency(4, 25);
+        for worker in 0..4 {
+            let events: Vec<&CounterEvent> = log.worker_events(worker).collect();
+            assert_eq!(events.len(), 25);
+            assert!(events.windows(2).all(|w| w[0].value < w[1].value));
+            assert!(events.iter().all(|e| e.thread_id == events[0].thread_id));
+        }
+    }
+
+    #[test]
+    fn test_timeline_has_a_line_per_event() {
+        let log = record_shared_memory_concurrency(2, 5);
+        let timeline = log.timeline();
+        let lines: Vec<&str> = timeline.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].contains("seq") && lines[0].contains("value"));
+        assert!(lines[10].trim_end().ends_with("10"));
+    }
+
+    #[test]
+    fn test_ascii_chart_marks_each_update_once() {
+        let log = record_shared_memory_concurrency(3, 10);
+        let chart = log.ascii_chart(80);
+        let rows: Vec<&str> = chart.lines().collect();
+        assert_eq!(rows.len(), 3);
+        for (worker, row) in rows.iter().enumerate() {
+            assert!(row.starts_with(&format!("worker {:>2} |", worker)));
+            assert_eq!(row.matches('#').count(), 10);
+        }
+        // Exactly one worker owns each column
+        let columns: Vec<Vec<char>> = rows
+            .iter()
+            .map(|row| row.split('|').nth(1).unwrap().chars().collect())
+            .collect();
+        for column in 0..30 {
+            assert_eq!(columns.iter().filter(|row| row[column] == '#').count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_ascii_chart_folds_long_logs() {
+        let log = record_shared_memory_concurrency(2, 50);
+        for row in log.ascii_chart(20).lines() {
+            assert_eq!(row.split('|').nth(1).unwrap().len(), 20);
+        }
+        assert_eq!(EventLog::default().ascii_chart(20), "");
+    }
+}
@@ -0,0 +1,95 @@
+use be_rust_master::multi_thread_processor::float_reduce::*;
+
+// Exactly rounded sum (Shewchuk's algorithm, as in Python's math.fsum) used as the high-precision reference
+fn exact_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut partials: Vec<f64> = Vec::new();
+    for mut x in values {
+        let mut i = 0;
+        for j in 0..partials.len() {
+            let mut y = partials[j];
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != 0.0 {
+                partials[i] = lo;
+                i += 1;
+            }
+            x = hi;
+        }
+        partials.truncate(i);
+        partials.push(x);
+    }
+    partials.iter().rev().sum()
+}
+
+// Ill-conditioned input: large values that cancel, mixed with many small ones
+fn test_data(len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|i| match i % 4 {
+            0 => 1e16,
+            1 => 0.1 * (i % 97) as f64,
+            2 => -1e16,
+            _ => 1.0 / (i as f64 + 1.0),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_matches_exact_reference() {
+        let data = test_data(100_003);
+        let expected = exact_sum(data.iter().copied());
+        let naive: f64 = data.iter().sum();
+        let result = parallel_sum(&data, 8);
+        assert!((result - expected).abs() <= expected.abs() * 1e-14);
+        assert!((result - expected).abs() < (naive - expected).abs());
+    }
+
+    #[test]
+    fn test_results_are_identical_for_every_thread_count() {
+        let data = test_data(50_001);
+        let other: Vec<f64> = data.iter().map(|x| x.sin()).collect();
+        let sum = parallel_sum(&data, 1);
+        let variance = parallel_variance(&data, 1);
+        let dot = parallel_dot(&data, &other, 1);
+        for threads in 2..=16 {
+            assert_eq!(parallel_sum(&data, threads).to_bits(), sum.to_bits());
+            assert_eq!(parallel_variance(&data, threads), variance);
+            assert_eq!(
+                parallel_dot(&data, &other, threads).to_bits(),
+                dot.to_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn test_mean_and_variance_match_reference() {
+        let data: Vec<f64> = (0..20_000).map(|i| 1e9 + (i % 10) as f64 * 0.1).collect();
+        let mean = exact_sum(data.iter().copied()) / data.len() as f64;
+        let variance = exact_sum(data.iter().map(|&x| (x - mean) * (x - mean))) / data.len() as f64;
+        assert!((parallel_mean(&data, 6).unwrap() - mean).abs() <= mean * 1e-15);
+        assert!((parallel_variance(&data, 6).unwrap() - variance).abs() <= variance * 1e-9);
+        assert_eq!(parallel_mean(&[], 4), None);
+        assert_eq!(parallel_variance(&[], 4), None);
+    }
+
+    #[test]
+    fn test_dot_matches_exact_reference() {
+        let a = test_data(10_000);
+        let b: Vec<f64> = (0..10_000).map(|i| (i % 3) as f64 - 1.0).collect();
+        let expected = exact_sum(a.iter().zip(&b).map(|(x, y)| x * y));
+        assert!((parallel_dot(&a, &b, 5) - expected).abs() <= expected.abs() * 1e-14);
+    }
+
+    #[test]
+    fn test_compensated_helpers() {
+        assert_eq!(kahan_sum([1.0, 1e100, 1.0, -1e100]), 2.0);
+        assert_eq!(pairwise_sum(&[]), 0.0);
+        assert_eq!(pairwise_sum(&[0.5, 0.25, 0.25]), 1.0);
+    }
+}
@@ -0,0 +1,111 @@
+use be_rust_master::shared_memory_concurrency::instrumented_mutex::*;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Instrumentation is opt-in; every test here wants it and none turns it off
+fn instrumented<T>(name: &'static str, value: T) -> InstrumentedMutex<T> {
+    set_instrumentation_enabled(true);
+    InstrumentedMutex::new(name, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_acquisitions_per_call_site() {
+        let mutex = instrumented("test::per_site", 0u32);
+        for _ in 0..3 {
+            *mutex.lock().unwrap() += 1;
+        }
+        *mutex.lock().unwrap() += 1;
+
+        let report = mutex.report();
+        assert_eq!(report.name, "test::per_site");
+        assert_eq!(report.acquisitions(), 4);
+        let mut counts: Vec<u64> = report.sites.iter().map(|s| s.stats.acquisitions).collect();
+        counts.sort();
+        assert_eq!(counts, vec![1, 3]);
+        assert!(report
+            .sites
+            .iter()
+            .all(|s| s.site.contains("instrumented_mutex.rs")));
+        assert_eq!(mutex.into_inner().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_hold_time_and_contention_are_recorded() {
+        let mutex = Arc::new(instrumented("test::contended", ()));
+        let guard = mutex.lock().unwrap();
+        let waiter = {
+            let mutex = Arc::clone(&mutex);
+            thread::spawn(move || drop(mutex.lock().unwrap()))
+        };
+        // Release only once the waiter is known to be blocked, so the contention is certain
+        while mutex.waiting() == 0 {
+            thread::yield_now();
+        }
+        thread::sleep(Duration::from_millis(20));
+        drop(guard);
+        waiter.join().unwrap();
+        assert_eq!(mutex.waiting(), 0);
+
+        let report = mutex.report();
+        assert_eq!(report.acquisitions(), 2);
+        assert_eq!(report.contended(), 1);
+        let hottest = &report.sites[0];
+        assert_eq!(hottest.stats.contended, 1);
+        assert!(hottest.stats.max_wait >= Duration::from_millis(20));
+        assert_eq!(*hottest.stats.wait_histogram.last().unwrap(), 1);
+        assert!(report
+            .sites
+            .iter()
+            .any(|s| s.stats.max_hold >= Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_acquisition_is_recorded_on_release() {
+        let mutex = instrumented("test::on_release", 0);
+        let guard = mutex.lock().unwrap();
+        assert_eq!(mutex.report().acquisitions(), 0);
+        drop(guard);
+        assert_eq!(mutex.report().acquisitions(), 1);
+    }
+
+    #[test]
+    fn test_mutexes_with_the_same_name_share_stats() {
+        let first = instrumented("test::shared_name", 1);
+        let second = instrumented("test::shared_name", 2);
+        drop(first.lock().unwrap());
+        drop(second.lock().unwrap());
+        assert_eq!(first.report().acquisitions(), 2);
+        assert!(contention_report()
+            .iter()
+            .any(|r| r.name == "test::shared_name" && r.acquisitions() == 2));
+    }
+
+    #[test]
+    fn test_poisoning_is_passed_through() {
+        let mutex = Arc::new(instrumented("test::poisoned", 7));
+        let writer = Arc::clone(&mutex);
+        let _ = thread::spawn(move || {
+            let _guard = writer.lock().unwrap();
+            panic!("writer failed");
+        })
+        .join();
+        let err = mutex.lock().unwrap_err();
+        assert_eq!(**err.get_ref(), 7);
+        drop(err);
+        assert_eq!(mutex.report().acquisitions(), 2);
+    }
+
+    #[test]
+    fn test_report_renders_sites_and_histogram() {
+        let mutex = instrumented("test::render", 0);
+        drop(mutex.lock().unwrap());
+        let text = mutex.report().to_string();
+        assert!(text.starts_with("lock `test::render`: 1 acquisitions, 0 contended"));
+        assert!(text.contains("wait histogram <1µs:"));
+    }
+}
@@ -0,0 +1,144 @@
+use be_rust_master::shared_memory_concurrency::lock_free::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const PRODUCERS: usize = 4;
+const CONSUMERS: usize = 4;
+const PER_PRODUCER: usize = 20_000;
+
+// Counts drops so tests can check every value is dropped exactly once
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Push from several producers while several consumers pop, return everything that was popped
+fn stress(push: impl Fn(usize) + Sync, pop: impl Fn() -> Option<usize> + Sync) -> Vec<usize> {
+    let done = AtomicUsize::new(0);
+    let popped = Mutex::new(vec![]);
+    thread::scope(|scope| {
+        for producer in 0..PRODUCERS {
+            let push = &push;
+            let done = &done;
+            scope.spawn(move || {
+                for i in 0..PER_PRODUCER {
+                    push(producer * PER_PRODUCER + i);
+                }
+                done.fetch_add(1, Ordering::Release);
+            });
+        }
+        for _ in 0..CONSUMERS {
+            scope.spawn(|| {
+                let mut local = vec![];
+                loop {
+                    match pop() {
+                        Some(value) => local.push(value),
+                        None if done.load(Ordering::Acquire) == PRODUCERS => {
+                            // Producers are finished, drain whatever is left
+                            while let Some(value) = pop() {
+                                local.push(value);
+                            }
+                            break;
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+                popped.lock().unwrap().extend(local);
+            });
+        }
+    });
+    let mut popped = popped.into_inner().unwrap();
+    popped.sort_unstable();
+    popped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_is_lifo() {
+        let stack = LockFreeStack::new();
+        assert!(stack.is_empty());
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert!(!stack.is_empty());
+        let popped: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+        assert_eq!(popped, vec![4, 3, 2, 1, 0]);
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_queue_is_fifo() {
+        let queue = LockFreeQueue::new();
+        assert!(queue.is_empty());
+        for i in 0..5 {
+            queue.push(i);
+        }
+        assert_eq!(queue.pop(), Some(0));
+        queue.push(5);
+        let popped: Vec<i32> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(popped, vec![1, 2, 3, 4, 5]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_stack_stress_pops_every_value_once() {
+        let stack = LockFreeStack::new();
+        let popped = stress(|v| stack.push(v), || stack.pop());
+        assert_eq!(popped, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_queue_stress_pops_every_value_once() {
+        let queue = LockFreeQueue::new();
+        let popped = stress(|v| queue.push(v), || queue.pop());
+        assert_eq!(popped, (0..PRODUCERS * PER_PRODUCER).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_queue_keeps_per_producer_order() {
+        let queue = Arc::new(LockFreeQueue::new());
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|producer| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        queue.push((producer, i));
+                    }
+                })
+            })
+            .collect();
+        for handle in producers {
+            handle.join().unwrap();
+        }
+        let mut next = [0; PRODUCERS];
+        while let Some((producer, i)) = queue.pop() {
+            assert_eq!(i, next[producer]);
+            next[producer] += 1;
+        }
+        assert_eq!(next, [PER_PRODUCER; PRODUCERS]);
+    }
+
+    #[test]
+    fn test_values_are_dropped_exactly_once() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        {
+            let stack = LockFreeStack::new();
+            let queue = LockFreeQueue::new();
+            for _ in 0..10 {
+                stack.push(Tracked(Arc::clone(&drops)));
+                queue.push(Tracked(Arc::clone(&drops)));
+            }
+            drop(stack.pop());
+            drop(queue.pop());
+            assert_eq!(drops.load(Ordering::Relaxed), 2);
+        }
+        assert_eq!(drops.load(Ordering::Relaxed), 20);
+    }
+}
@@ -0,0 +1,78 @@
+// Model checks that explore every interleaving of the lock-free structures, up to a preemption bound.
+// The epoch collector only switches to loom's atomics under its own cfg, so run with:
+// LOOM_MAX_PREEMPTIONS=2 RUSTFLAGS="--cfg crossbeam_loom" cargo test --features loom --test lock_free_loom --release
+// The preemption bound keeps the search over the epoch collector's internals to seconds.
+#![cfg(all(feature = "loom", crossbeam_loom))]
+
+use be_rust_master::shared_memory_concurrency::lock_free::*;
+use loom::sync::Arc;
+use loom::thread;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_concurrent_push_pop() {
+        loom::model(|| {
+            let stack = Arc::new(LockFreeStack::new());
+            let pusher = {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    stack.push(1);
+                    stack.push(2);
+                })
+            };
+            let popped = stack.pop();
+            pusher.join().unwrap();
+
+            let mut all: Vec<i32> = popped.into_iter().collect();
+            all.extend(std::iter::from_fn(|| stack.pop()));
+            all.sort();
+            assert_eq!(all, vec![1, 2]);
+        });
+    }
+
+    #[test]
+    fn test_queue_concurrent_producer_and_consumer() {
+        loom::model(|| {
+            let queue = Arc::new(LockFreeQueue::new());
+            let producer = {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    queue.push(1);
+                    queue.push(2);
+                })
+            };
+            let popped = queue.pop();
+            producer.join().unwrap();
+
+            // Whatever was popped early must be the first value pushed
+            let all: Vec<i32> = popped
+                .into_iter()
+                .chain(std::iter::from_fn(|| queue.pop()))
+                .collect();
+            assert_eq!(all, vec![1, 2]);
+        });
+    }
+
+    #[test]
+    fn test_queue_racing_consumers_take_each_value_once() {
+        loom::model(|| {
+            let queue = Arc::new(LockFreeQueue::new());
+            queue.push(1);
+            queue.push(2);
+            let consumer = {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || queue.pop())
+            };
+            let mine = queue.pop();
+            let theirs = consumer.join().unwrap();
+
+            let mut all: Vec<i32> = mine.into_iter().chain(theirs).collect();
+            all.sort();
+            assert_eq!(all, vec![1, 2]);
+            assert!(queue.is_empty());
+        });
+    }
+}
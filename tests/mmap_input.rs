@@ -0,0 +1,79 @@
+use be_rust_master::multi_thread_processor::mmap_input::*;
+use std::path::PathBuf;
+
+// Write `bytes` to a fresh file in the temp directory; the caller removes it
+fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mmap_input_{}_{}.bin", name, std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u64_file_is_viewed_in_place() {
+        let values: Vec<u64> = (0..10_001).map(|x| x * 3).collect();
+        let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let path = temp_file("u64", &bytes);
+
+        let array = MappedArray::<u64>::open(&path).unwrap();
+        assert_eq!(array.len(), values.len());
+        assert_eq!(array.as_slice(), values.as_slice());
+
+        let chunk_sums = array.map_chunks(4, |chunk| chunk.iter().sum::<u64>());
+        assert_eq!(chunk_sums.len(), 4);
+        assert_eq!(chunk_sums.iter().sum::<u64>(), values.iter().sum::<u64>());
+
+        let expected: u64 = values.iter().map(|x| x * x).sum();
+        assert_eq!(mapped_sum_of_squares(&path, 6).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // Overflow checks only exist in debug builds, matching the in-memory sum_of_squares
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_sum_of_squares_overflow_panics_like_in_memory_input() {
+        let path = temp_file("overflow", &u64::MAX.to_le_bytes());
+        let result = std::panic::catch_unwind(|| mapped_sum_of_squares(&path, 2));
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_f64_file_sum() {
+        let values: Vec<f64> = (0..1000).map(|x| x as f64 * 0.25).collect();
+        let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let path = temp_file("f64", &bytes);
+        assert_eq!(mapped_f64_sum(&path, 3).unwrap(), 124_875.0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_file_is_rejected() {
+        let path = temp_file("truncated", &[0u8; 21]);
+        let err = MappedArray::<u64>::open(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            err,
+            MmapError::Truncated {
+                file_len: 21,
+                element_size: 8
+            }
+        ));
+        assert!(err.to_string().contains("5 trailing bytes"));
+    }
+
+    #[test]
+    fn test_empty_and_missing_files() {
+        let path = temp_file("empty", &[]);
+        let array = MappedArray::<f64>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(array.is_empty());
+        assert_eq!(array.as_slice(), &[] as &[f64]);
+
+        let err = mapped_sum_of_squares("/nonexistent/mmap_input.bin", 2).unwrap_err();
+        assert!(matches!(err, MmapError::Io(_)));
+    }
+}
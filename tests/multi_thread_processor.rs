@@ -0,0 +1,80 @@
+use be_rust_master::multi_thread_processor::control::*;
+use be_rust_master::multi_thread_processor::isolation::*;
+use be_rust_master::multi_thread_processor::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_ranges_cover_input() {
+        let ranges = chunk_ranges(10, 3);
+        assert_eq!(ranges, vec![0..3, 3..6, 6..10]);
+        assert_eq!(chunk_ranges(2, 4), vec![0..0, 0..0, 0..0, 0..2]);
+        assert_eq!(chunk_ranges(5, 0), vec![0..5]);
+    }
+
+    #[test]
+    fn test_multi_thread_result_reports_timings() {
+        let data = Arc::new((1..=1000u64).collect::<Vec<_>>());
+        let result = multi_thread_sum_of_squares(Arc::clone(&data), 6).unwrap();
+        assert_eq!(result.value, sum_of_squares(&data));
+        assert_eq!(result.threads, 6);
+        assert_eq!(result.thread_timings.len(), 6);
+        assert!(result.thread_timings.iter().all(|&t| t <= result.elapsed));
+    }
+
+    #[test]
+    fn test_worker_panic_is_reported_with_chunk() {
+        // The progress hook runs on the worker between sub-chunks, so panicking from it fails
+        // chunk 3 partway through in every build profile
+        let control = JobControl {
+            progress: Some(Arc::new(|p: Progress| {
+                if p.worker == 3 {
+                    panic!("hook failed after {} elements", p.processed);
+                }
+            })),
+            sub_chunk_size: 10,
+            ..JobControl::default()
+        };
+        let err = controlled_sum_of_squares(Arc::new(vec![1u64; 100]), 4, 0, &control);
+        let Err(JobError::Panicked(err)) = err else {
+            panic!("expected chunk 3 to fail");
+        };
+        assert_eq!(err.chunk, 3);
+        assert_eq!(err.range, 75..100);
+        assert_eq!(err.attempts, 1);
+        assert_eq!(err.message, "hook failed after 10 elements");
+    }
+
+    #[test]
+    fn test_failed_chunk_is_retried() {
+        let data: Vec<u64> = (0..100).collect();
+        let failures = AtomicUsize::new(0);
+        let results = run_isolated(&data, 4, 2, |chunk, values| {
+            // Chunk 2 fails on its first two attempts only
+            if chunk == 2 && failures.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!("transient failure");
+            }
+            values.iter().sum::<u64>()
+        })
+        .unwrap();
+        let sums: Vec<u64> = results.iter().map(|&(sum, _)| sum).collect();
+        assert_eq!(sums, vec![300, 925, 1550, 2175]);
+
+        let err = run_isolated(&data, 4, 1, |chunk, _| {
+            if chunk >= 1 {
+                panic!("chunk {} is broken", chunk);
+            }
+        })
+        .unwrap_err();
+        assert_eq!(err.chunk, 1);
+        assert_eq!(err.attempts, 2);
+        assert_eq!(
+            err.to_string(),
+            "worker for chunk 1 (25..50) panicked after 2 attempt(s): chunk 1 is broken"
+        );
+    }
+}
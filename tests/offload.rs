@@ -0,0 +1,77 @@
+use be_rust_master::multi_thread_processor::offload::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrency_limit_is_respected() {
+        for backend in [Backend::BlockingPool, Backend::DedicatedThread] {
+            let offloader = Offloader::with_backend(3, backend);
+            let active = Arc::new(AtomicUsize::new(0));
+            let peak = Arc::new(AtomicUsize::new(0));
+
+            let jobs = (0..12).map(|i| {
+                let (active, peak) = (Arc::clone(&active), Arc::clone(&peak));
+                offloader.run(move || {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    i * 2
+                })
+            });
+            let results: Vec<_> = futures::future::join_all(jobs).await;
+
+            assert_eq!(results, (0..12).map(|i| Ok(i * 2)).collect::<Vec<_>>());
+            assert!(peak.load(Ordering::SeqCst) <= 3);
+            assert_eq!(offloader.available_slots(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_job_is_an_error() {
+        for backend in [Backend::BlockingPool, Backend::DedicatedThread] {
+            let offloader = Offloader::with_backend(1, backend);
+            let result = offloader.run(|| -> u32 { panic!("job exploded") }).await;
+            assert_eq!(
+                result,
+                Err(OffloadError::Panicked(String::from("job exploded")))
+            );
+            // The slot is released after the panic
+            assert_eq!(offloader.run(|| 7).await, Ok(7));
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_blocked_job_does_not_block_the_runtime() {
+        for backend in [Backend::BlockingPool, Backend::DedicatedThread] {
+            let offloader = Offloader::with_backend(2, backend);
+            // The job blocks until a task on this single-threaded runtime releases it.
+            // Run on the runtime thread, it would wait out the timeout and report false.
+            let (release, released) = std::sync::mpsc::channel();
+            let job = offloader.run(move || released.recv_timeout(Duration::from_secs(5)).is_ok());
+            let releaser = tokio::spawn(async move {
+                tokio::task::yield_now().await;
+                release.send(()).unwrap();
+                "released"
+            });
+            assert_eq!(job.await, Ok(true));
+            assert_eq!(releaser.await.unwrap(), "released");
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_sum_of_squares_result() {
+        let offloader = Offloader::new(2);
+        let result = offloader
+            .sum_of_squares(Arc::new(vec![3; 1000]), 4)
+            .await
+            .unwrap();
+        assert_eq!(result.value, 9000);
+        assert_eq!(offloader.available_slots(), 2);
+    }
+}
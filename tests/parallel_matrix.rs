@@ -0,0 +1,93 @@
+use be_rust_master::benchmark::{run_matrix_benchmarks, BenchmarkConfig};
+use be_rust_master::parallel_matrix::*;
+
+// Small integer entries keep every product and sum exact, so blocked and naive results match bit for bit
+fn sample(rows: usize, cols: usize, salt: usize) -> Matrix {
+    Matrix::from_fn(rows, cols, |r, c| {
+        ((r * 31 + c * 17 + salt) % 9) as f64 - 4.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiply_matches_naive() {
+        // Sizes straddle the 64-element tile edge
+        for (n, k, m) in [(1, 1, 1), (3, 5, 2), (65, 63, 130), (128, 64, 1), (0, 4, 3)] {
+            let a = sample(n, k, 1);
+            let b = sample(k, m, 2);
+            let expected = a.multiply_naive(&b).unwrap();
+            for threads in [1, 3, 8] {
+                assert_eq!(a.multiply(&b, threads).unwrap(), expected);
+            }
+        }
+        assert_eq!(
+            sample(20, 20, 0)
+                .multiply(&Matrix::identity(20), 4)
+                .unwrap(),
+            sample(20, 20, 0)
+        );
+    }
+
+    #[test]
+    fn test_transpose_matches_naive() {
+        for (rows, cols) in [(1, 1), (70, 3), (3, 130), (129, 65)] {
+            let a = sample(rows, cols, 5);
+            let expected = a.transpose_naive();
+            assert_eq!(expected.rows(), cols);
+            for threads in [1, 4, 16] {
+                assert_eq!(a.transpose(threads), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_vector_product() {
+        let a = sample(100, 37, 3);
+        let v: Vec<f64> = (0..37).map(|i| i as f64).collect();
+        let as_column = Matrix::from_vec(37, 1, v.clone()).unwrap();
+        let expected = a.multiply_naive(&as_column).unwrap();
+        assert_eq!(a.mul_vec(&v, 6).unwrap(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_dimension_errors() {
+        let a = Matrix::zeros(2, 3);
+        assert_eq!(
+            a.multiply(&Matrix::zeros(2, 3), 2),
+            Err(MatrixError::DimensionMismatch {
+                left: (2, 3),
+                right: (2, 3)
+            })
+        );
+        assert_eq!(
+            a.mul_vec(&[1.0], 2),
+            Err(MatrixError::DimensionMismatch {
+                left: (2, 3),
+                right: (1, 1)
+            })
+        );
+        assert_eq!(
+            Matrix::from_vec(2, 2, vec![1.0; 3]),
+            Err(MatrixError::InvalidLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_matrix_benchmark_reports_each_thread_count() {
+        let config = BenchmarkConfig {
+            warmup_runs: 0,
+            runs: 2,
+            thread_counts: vec![1, 2],
+            ..BenchmarkConfig::default()
+        };
+        let results = run_matrix_benchmarks(&[16, 32], &config);
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.speedup > 0.0));
+    }
+}
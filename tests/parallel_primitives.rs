@@ -0,0 +1,81 @@
+use be_rust_master::multi_thread_processor::parallel_primitives::*;
+use proptest::prelude::*;
+use std::cmp::Ordering;
+
+// Ordered by key only, so a stable sort must keep equal keys in input order
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Keyed(u8, usize);
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn merge_sort_matches_reference(data in prop::collection::vec(any::<i32>(), 0..2000), threads in 1usize..17) {
+            let mut expected = data.clone();
+            sequential_merge_sort(&mut expected);
+            let mut actual = data;
+            parallel_merge_sort(&mut actual, threads);
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn merge_sort_is_stable(keys in prop::collection::vec(0u8..8, 0..500), threads in 1usize..9) {
+            let data: Vec<Keyed> = keys.iter().enumerate().map(|(i, &k)| Keyed(k, i)).collect();
+            let mut expected = data.clone();
+            sequential_merge_sort(&mut expected);
+            let mut actual = data;
+            parallel_merge_sort(&mut actual, threads);
+            let indices = |v: &[Keyed]| v.iter().map(|k| k.1).collect::<Vec<_>>();
+            prop_assert_eq!(indices(&actual), indices(&expected));
+        }
+
+        #[test]
+        fn binary_search_matches_reference(
+            mut data in prop::collection::vec(0u16..500, 0..1000),
+            target in 0u16..520,
+            threads in 1usize..17,
+        ) {
+            data.sort();
+            prop_assert_eq!(
+                parallel_binary_search(&data, &target, threads),
+                sequential_binary_search(&data, &target)
+            );
+        }
+
+        #[test]
+        fn prefix_sum_matches_reference(data in prop::collection::vec(-1000i64..1000, 0..2000), threads in 1usize..17) {
+            prop_assert_eq!(parallel_prefix_sum(&data, threads), sequential_prefix_sum(&data));
+        }
+
+        #[test]
+        fn filter_and_partition_match_reference(data in prop::collection::vec(any::<u32>(), 0..2000), threads in 1usize..17) {
+            let is_even = |x: &u32| x.is_multiple_of(2);
+            prop_assert_eq!(parallel_filter(&data, threads, is_even), sequential_filter(&data, is_even));
+            prop_assert_eq!(parallel_partition(&data, threads, is_even), sequential_partition(&data, is_even));
+        }
+    }
+
+    #[test]
+    fn test_binary_search_returns_first_occurrence() {
+        let data = [1, 3, 3, 3, 3, 3, 3, 7, 9];
+        for threads in 1..=9 {
+            assert_eq!(parallel_binary_search(&data, &3, threads), Ok(1));
+            assert_eq!(parallel_binary_search(&data, &8, threads), Err(8));
+            assert_eq!(parallel_binary_search(&data, &0, threads), Err(0));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use be_rust_master::pipeline::*;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items_flow_through_every_stage() {
+        let (mut output, report) = Pipeline::source(1..=1000u64, 8)
+            .map("square", 4, |x| x * x)
+            .map("to_string", 2, |x| x.to_string())
+            .collect();
+        output.sort_by_key(|s| s.parse::<u64>().unwrap());
+        let expected: Vec<String> = (1..=1000u64).map(|x| (x * x).to_string()).collect();
+        assert_eq!(output, expected);
+
+        let names: Vec<&str> = report.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["source", "square", "to_string", "sink"]);
+        let workers: Vec<usize> = report.stages.iter().map(|s| s.workers).collect();
+        assert_eq!(workers, vec![1, 4, 2, 1]);
+        assert!(report.stages.iter().all(|s| s.items == 1000));
+        assert!(report.stages.iter().all(|s| s.elapsed <= report.elapsed));
+    }
+
+    #[test]
+    fn test_single_worker_stages_keep_order() {
+        let mut seen = vec![];
+        Pipeline::source(vec!["a", "b", "c"], 1)
+            .map("upper", 1, |s: &str| s.to_uppercase())
+            .sink(|s| seen.push(s));
+        assert_eq!(seen, vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_slow_stage_benefits_from_more_workers() {
+        let slow = |x: u32| {
+            std::thread::sleep(Duration::from_millis(2));
+            x
+        };
+        let (_, report) = Pipeline::source(0..40u32, 4).map("slow", 8, slow).collect();
+        let stage = &report.stages[1];
+        // Eight workers overlap their sleeps, so busy time adds up to more than the stage's wall time
+        assert!(stage.busy > stage.elapsed);
+        assert!(stage.throughput() > 0.0);
+        assert!(report.to_string().contains("slow"));
+    }
+
+    #[test]
+    fn test_empty_source() {
+        let (output, report) = Pipeline::source(Vec::<u8>::new(), 2)
+            .map("id", 3, |x| x)
+            .collect();
+        assert!(output.is_empty());
+        assert!(report.stages.iter().all(|s| s.items == 0));
+    }
+}
@@ -0,0 +1,140 @@
+use be_rust_master::shared_memory_concurrency::instrumented_mutex::{
+    set_instrumentation_enabled, InstrumentedMutex,
+};
+use be_rust_master::shared_memory_concurrency::poison::*;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+// Push `value` and then panic while still holding the lock, leaving it poisoned
+fn poison_after_push(lock: &Arc<Mutex<Vec<i32>>>, value: i32) {
+    let lock = Arc::clone(lock);
+    let result = thread::spawn(move || {
+        let mut guard = lock.lock().unwrap();
+        guard.push(value);
+        panic!("writer failed inside the critical section");
+    })
+    .join();
+    assert!(result.is_err());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propagate_reports_poisoning() {
+        let lock = Arc::new(Mutex::new(vec![1]));
+        poison_after_push(&lock, 2);
+        assert_eq!(lock.lock_or_propagate().unwrap_err(), LockPoisoned);
+        assert!(lock.lock_with(PoisonPolicy::Propagate).is_err());
+        // Propagating leaves the lock poisoned for everyone else too
+        assert!(lock.is_poisoned());
+    }
+
+    #[test]
+    fn test_recover_keeps_partial_update() {
+        let lock = Arc::new(Mutex::new(vec![1]));
+        poison_after_push(&lock, 2);
+        assert_eq!(*lock.lock_or_recover(), vec![1, 2]);
+        assert!(!lock.is_poisoned());
+        assert_eq!(*lock.lock_or_propagate().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_reset_replaces_value_with_default() {
+        let lock = Arc::new(Mutex::new(vec![1]));
+        poison_after_push(&lock, 2);
+        assert!(lock
+            .lock_with_reset(PoisonPolicy::ResetToDefault)
+            .unwrap()
+            .is_empty());
+        assert!(!lock.is_poisoned());
+    }
+
+    #[test]
+    fn test_healthy_lock_is_unaffected_by_policy() {
+        let lock = Mutex::new(5);
+        for policy in [
+            PoisonPolicy::Propagate,
+            PoisonPolicy::Recover,
+            PoisonPolicy::ResetToDefault,
+        ] {
+            assert_eq!(*lock.lock_with_reset(policy).unwrap(), 5);
+        }
+        assert_eq!(PoisonPolicy::default(), PoisonPolicy::Propagate);
+    }
+
+    #[test]
+    fn test_policies_without_reset_accept_non_default_values() {
+        // No `Default` impl: only the reset paths need one
+        #[derive(Debug, PartialEq)]
+        struct Port(u16);
+
+        let lock = Arc::new(Mutex::new(Port(80)));
+        let writer = Arc::clone(&lock);
+        let _ = thread::spawn(move || {
+            let mut guard = writer.lock().unwrap();
+            guard.0 = 8080;
+            panic!("writer failed inside the critical section");
+        })
+        .join();
+
+        assert!(lock.lock_with(PoisonPolicy::Propagate).is_err());
+        assert_eq!(*lock.lock_with(PoisonPolicy::Recover).unwrap(), Port(8080));
+        assert_eq!(
+            *lock.lock_with(PoisonPolicy::Propagate).unwrap(),
+            Port(8080)
+        );
+    }
+
+    #[test]
+    fn test_policies_apply_to_rwlock_reads_and_writes() {
+        let lock = Arc::new(RwLock::new(vec![1]));
+        let poison = |value: i32| {
+            let writer = Arc::clone(&lock);
+            let result = thread::spawn(move || {
+                writer.write().unwrap().push(value);
+                let _guard = writer.write().unwrap();
+                panic!("writer failed inside the critical section");
+            })
+            .join();
+            assert!(result.is_err());
+        };
+
+        poison(2);
+        assert_eq!(lock.read_or_propagate().unwrap_err(), LockPoisoned);
+        assert!(lock.lock_or_propagate().is_err());
+        assert_eq!(*lock.read_or_recover(), vec![1, 2]);
+        assert!(!lock.is_poisoned());
+
+        poison(3);
+        assert!(lock
+            .read_with_reset(PoisonPolicy::ResetToDefault)
+            .unwrap()
+            .is_empty());
+        assert!(!lock.is_poisoned());
+    }
+
+    #[test]
+    fn test_policies_apply_to_instrumented_mutex() {
+        set_instrumentation_enabled(true);
+        let lock = Arc::new(InstrumentedMutex::new("test::poison_policy", 10));
+        let writer = Arc::clone(&lock);
+        let _ = thread::spawn(move || {
+            let mut guard = writer.lock_or_recover();
+            *guard += 1;
+            panic!("writer failed inside the critical section");
+        })
+        .join();
+
+        assert!(lock.lock_or_propagate().is_err());
+        assert_eq!(*lock.lock_or_reset(), 0);
+        assert_eq!(*lock.lock_or_propagate().unwrap(), 0);
+        // Sites are still attributed to the caller, not to the policy helpers
+        assert!(lock
+            .report()
+            .sites
+            .iter()
+            .all(|s| s.site.starts_with("tests/poison.rs")));
+    }
+}
@@ -0,0 +1,73 @@
+use be_rust_master::multi_thread_processor::rng::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splitmix64_reference_values() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+        assert_eq!(rng.next_u64(), 0x06c4_5d18_8009_454f);
+    }
+
+    #[test]
+    fn test_streams_are_reproducible_and_distinct() {
+        let draw = |mut rng: Xoshiro256| (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            draw(Xoshiro256::stream(42, 7)),
+            draw(Xoshiro256::stream(42, 7))
+        );
+        assert_ne!(
+            draw(Xoshiro256::stream(42, 7)),
+            draw(Xoshiro256::stream(42, 8))
+        );
+        assert_ne!(
+            draw(Xoshiro256::stream(42, 7)),
+            draw(Xoshiro256::stream(43, 7))
+        );
+
+        // Pairs that collided when seed and index were only xor-ed together
+        let c = 0xd1b5_4a32_d192_ed03u64;
+        assert_ne!(
+            draw(Xoshiro256::stream(1, 0)),
+            draw(Xoshiro256::stream(1 ^ c, 1))
+        );
+
+        let mut parent = Xoshiro256::seed_from_u64(1);
+        let child = parent.split();
+        assert_ne!(draw(child), draw(parent));
+
+        let mut rng = Xoshiro256::seed_from_u64(9);
+        assert!((0..10_000)
+            .map(|_| rng.next_f64())
+            .all(|x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn test_pi_is_identical_for_every_thread_count() {
+        let samples = 1_000_000;
+        let reference = estimate_pi(samples, 2024, 1);
+        assert!((reference - std::f64::consts::PI).abs() < 0.01);
+        for threads in 2..=12 {
+            assert_eq!(
+                estimate_pi(samples, 2024, threads).to_bits(),
+                reference.to_bits()
+            );
+        }
+        assert_ne!(estimate_pi(samples, 2025, 4), reference);
+    }
+
+    #[test]
+    fn test_integral_estimate() {
+        // Integral of x^2 over [0, 3] is 9
+        let estimate = estimate_integral(|x| x * x, 0.0, 3.0, 500_000, 7, 5);
+        assert!((estimate - 9.0).abs() < 0.05);
+        assert_eq!(
+            estimate,
+            estimate_integral(|x| x * x, 0.0, 3.0, 500_000, 7, 1)
+        );
+        assert!(parallel_monte_carlo(0, 1, 4, |_| 1.0).is_nan());
+    }
+}
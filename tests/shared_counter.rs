@@ -0,0 +1,54 @@
+use be_rust_master::shared_memory_concurrency::counter::*;
+use be_rust_master::shared_memory_concurrency::demonstrate_shared_memory_concurrency;
+use std::sync::Arc;
+
+fn backends() -> Vec<(&'static str, Arc<dyn SharedCounter>)> {
+    vec![
+        ("mutex", Arc::new(MutexCounter::new())),
+        ("atomic", Arc::new(AtomicCounter::new())),
+        ("sharded", Arc::new(ShardedCounter::with_shards(4))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_backend_counts_every_increment() {
+        for (name, counter) in backends() {
+            let (value, _) = run_counter(counter, 8, 10_000);
+            assert_eq!(value, 80_000, "backend {}", name);
+        }
+    }
+
+    #[test]
+    fn test_add_and_get_through_trait() {
+        for (name, counter) in backends() {
+            counter.add(5);
+            counter.increment();
+            assert_eq!(counter.get(), 6, "backend {}", name);
+        }
+    }
+
+    #[test]
+    fn test_sharded_counter_with_more_threads_than_shards() {
+        let counter: Arc<dyn SharedCounter> = Arc::new(ShardedCounter::with_shards(1));
+        let (value, _) = run_counter(counter, 6, 1_000);
+        assert_eq!(value, 6_000);
+    }
+
+    #[test]
+    fn test_compare_counters_reports_all_backends() {
+        let runs = compare_counters(4, 2_500);
+        let names: Vec<&str> = runs.iter().map(|run| run.backend).collect();
+        assert_eq!(names, vec!["mutex", "atomic", "sharded", "thread-local"]);
+        assert!(runs.iter().all(|run| run.final_value == 10_000));
+        assert!(runs.iter().all(|run| run.increments_per_sec() > 0.0));
+    }
+
+    #[test]
+    fn test_demonstration_returns_final_value() {
+        assert_eq!(demonstrate_shared_memory_concurrency(), 50);
+    }
+}
@@ -0,0 +1,126 @@
+use be_rust_master::multi_thread_processor::simd::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lengths around the lane width so both the vector body and the remainder loop are exercised
+    const LENGTHS: [usize; 6] = [0, 1, 7, 8, 9, 1003];
+
+    #[test]
+    fn test_u64_kernels_match_scalar() {
+        for len in LENGTHS {
+            let data: Vec<u64> = (0..len as u64).collect();
+            assert_eq!(sum(&data), data.iter().sum::<u64>());
+            assert_eq!(
+                sum_of_squares(&data),
+                data.iter().map(|&x| x * x).sum::<u64>()
+            );
+            assert_eq!(dot(&data, &data), sum_of_squares(&data));
+            assert_eq!(min_max(&data), data.first().map(|_| (0, len as u64 - 1)));
+        }
+    }
+
+    #[test]
+    fn test_i64_kernels_match_scalar() {
+        for len in LENGTHS {
+            let data: Vec<i64> = (0..len as i64).map(|x| x * 7 % 13 - 6).collect();
+            let reversed: Vec<i64> = data.iter().rev().copied().collect();
+            assert_eq!(sum(&data), data.iter().sum::<i64>());
+            assert_eq!(
+                sum_of_squares(&data),
+                data.iter().map(|&x| x * x).sum::<i64>()
+            );
+            assert_eq!(
+                dot(&data, &reversed),
+                data.iter()
+                    .zip(&reversed)
+                    .map(|(&a, &b)| a * b)
+                    .sum::<i64>()
+            );
+            let expected = data
+                .first()
+                .map(|_| (*data.iter().min().unwrap(), *data.iter().max().unwrap()));
+            assert_eq!(min_max(&data), expected);
+        }
+    }
+
+    #[test]
+    fn test_float_kernels_match_scalar() {
+        for len in LENGTHS {
+            let data: Vec<f64> = (0..len).map(|x| x as f64 * 0.5 - 100.0).collect();
+            let expected: f64 = data.iter().map(|&x| x * x).sum();
+            assert!((sum_of_squares(&data) - expected).abs() <= expected * 1e-12);
+            assert!((sum(&data) - data.iter().sum::<f64>()).abs() < 1e-9);
+
+            let data: Vec<f32> = (0..len).map(|x| (x % 17) as f32).collect();
+            assert_eq!(sum(&data), data.iter().sum::<f32>());
+            assert_eq!(dot(&data, &data), sum_of_squares(&data));
+        }
+    }
+
+    #[test]
+    fn test_float_min_max_ignores_nan() {
+        let data = [
+            3.0f32,
+            f32::NAN,
+            -2.5,
+            9.0,
+            f32::NAN,
+            1.0,
+            0.0,
+            4.0,
+            8.5,
+            -1.0,
+        ];
+        assert_eq!(min_max(&data), Some((-2.5, 9.0)));
+
+        let (lo, hi) = min_max(&[f64::NAN; 11]).unwrap();
+        assert!(lo.is_nan() && hi.is_nan());
+    }
+
+    #[test]
+    fn test_u64_kernels_wrap_and_order_high_bit() {
+        // Values above i64::MAX and products that overflow 64 bits
+        let data: Vec<u64> = (0..1003u64)
+            .map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (x << 40))
+            .collect();
+        let reversed: Vec<u64> = data.iter().rev().copied().collect();
+        assert_eq!(
+            sum(&data),
+            data.iter().fold(0u64, |a, &x| a.wrapping_add(x))
+        );
+        assert_eq!(
+            dot(&data, &reversed),
+            data.iter()
+                .zip(&reversed)
+                .fold(0u64, |a, (&x, &y)| a.wrapping_add(x.wrapping_mul(y)))
+        );
+        assert_eq!(
+            min_max(&data),
+            Some((*data.iter().min().unwrap(), *data.iter().max().unwrap()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn test_dot_rejects_mismatched_lengths() {
+        dot(&[1u64, 2], &[1u64]);
+    }
+
+    #[test]
+    fn test_parallel_kernels_match_single_threaded() {
+        let data: Vec<u64> = (0..10_007).collect();
+        for threads in [1, 3, 12, 20_000] {
+            assert_eq!(parallel_sum(&data, threads), sum(&data));
+            assert_eq!(
+                parallel_sum_of_squares(&data, threads),
+                sum_of_squares(&data)
+            );
+            assert_eq!(parallel_dot(&data, &data, threads), sum_of_squares(&data));
+            assert_eq!(parallel_min_max(&data, threads), Some((0, 10_006)));
+        }
+        assert_eq!(parallel_min_max::<f64>(&[], 4), None);
+        assert_eq!(parallel_sum::<i64>(&[], 4), 0);
+    }
+}
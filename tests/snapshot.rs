@@ -0,0 +1,190 @@
+use be_rust_master::shared_memory_concurrency::snapshot::*;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, PartialEq)]
+struct ServiceConfig {
+    workers: usize,
+    greeting: String,
+}
+
+// "workers=<n>\ngreeting=<text>"
+fn parse_config(text: &str) -> Result<ServiceConfig, String> {
+    let mut workers = None;
+    let mut greeting = None;
+    for line in text.lines() {
+        match line.split_once('=') {
+            Some(("workers", value)) => workers = value.trim().parse().ok(),
+            Some(("greeting", value)) => greeting = Some(value.trim().to_string()),
+            _ => return Err(format!("unexpected line `{}`", line)),
+        }
+    }
+    Ok(ServiceConfig {
+        workers: workers.ok_or("missing workers")?,
+        greeting: greeting.ok_or("missing greeting")?,
+    })
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("snapshot_{}_{}.conf", name, std::process::id()))
+}
+
+// Replace `path` atomically, so the reloader only ever sees the old or the new contents
+fn replace_file(path: &PathBuf, contents: &str, modified: Option<SystemTime>) {
+    let staging = path.with_extension("staging");
+    std::fs::write(&staging, contents).unwrap();
+    if let Some(modified) = modified {
+        std::fs::File::options()
+            .write(true)
+            .open(&staging)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+    std::fs::rename(&staging, path).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_update_publish_new_versions() {
+        let cell = SnapshotCell::new(1u64);
+        let old = cell.load();
+        assert_eq!(cell.store(2), 1);
+        assert_eq!(cell.update(|v| v * 10), 2);
+        assert_eq!(cell.load_versioned(), (2, Arc::new(20)));
+        // A reader keeps the snapshot it loaded
+        assert_eq!(*old, 1);
+    }
+
+    #[test]
+    fn test_readers_always_see_consistent_snapshots() {
+        // Writers keep both fields equal, a torn read would show them differ
+        let cell = SnapshotCell::new((0u64, 0u64));
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut last_version = 0;
+                    for _ in 0..20_000 {
+                        let (version, value) = cell.load_versioned();
+                        assert_eq!(value.0, value.1);
+                        assert!(version >= last_version);
+                        last_version = version;
+                    }
+                });
+            }
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    for _ in 0..1_000 {
+                        cell.update(|&(a, _)| (a + 1, a + 1));
+                    }
+                });
+            }
+        });
+        assert_eq!(cell.load_versioned(), (2_000, Arc::new((2_000, 2_000))));
+    }
+
+    #[test]
+    fn test_reload_from_file_keeps_previous_on_error() {
+        let path = temp_path("manual");
+        let cell = SnapshotCell::new(parse_config("workers=1\ngreeting=hi").unwrap());
+
+        std::fs::write(&path, "workers=4\ngreeting=hello").unwrap();
+        assert_eq!(reload_from_file(&cell, &path, parse_config).unwrap(), 1);
+        assert_eq!(cell.load().workers, 4);
+
+        std::fs::write(&path, "workers=four").unwrap();
+        let err = reload_from_file(&cell, &path, parse_config).unwrap_err();
+        assert!(matches!(err, ReloadError::Parse(_)));
+        assert_eq!(cell.version(), 1);
+        assert_eq!(cell.load().greeting, "hello");
+
+        std::fs::remove_file(&path).unwrap();
+        let err = reload_from_file(&cell, &path, parse_config).unwrap_err();
+        assert!(matches!(err, ReloadError::Io(_)));
+    }
+
+    #[test]
+    fn test_file_reloader_publishes_changes() {
+        let path = temp_path("watched");
+        std::fs::write(&path, "workers=1\ngreeting=hi").unwrap();
+        let cell = Arc::new(SnapshotCell::new(
+            parse_config("workers=1\ngreeting=hi").unwrap(),
+        ));
+
+        let (sender, receiver) = mpsc::channel();
+        let reloader = FileReloader::spawn(
+            Arc::clone(&cell),
+            &path,
+            Duration::from_millis(5),
+            parse_config,
+            move |result| sender.send(result.map_err(|e| e.to_string())).unwrap(),
+        );
+        assert_eq!(reloader.path(), path.as_path());
+
+        replace_file(&path, "workers=8\ngreeting=hello there", None);
+        let outcome = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(outcome, Ok(1));
+        assert_eq!(
+            *cell.load(),
+            ServiceConfig {
+                workers: 8,
+                greeting: "hello there".to_string()
+            }
+        );
+
+        drop(reloader);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_reloader_sees_same_length_rewrite() {
+        let path = temp_path("same_length");
+        std::fs::write(&path, "workers=1\ngreeting=hi").unwrap();
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        let cell = Arc::new(SnapshotCell::new(
+            parse_config("workers=1\ngreeting=hi").unwrap(),
+        ));
+
+        let (sender, receiver) = mpsc::channel();
+        let reloader = FileReloader::spawn(
+            Arc::clone(&cell),
+            &path,
+            Duration::from_millis(5),
+            parse_config,
+            move |result| sender.send(result.map_err(|e| e.to_string())).unwrap(),
+        );
+
+        // Same length and, as within a coarse timestamp tick, the same modification time
+        replace_file(&path, "workers=2\ngreeting=yo", Some(modified));
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            modified
+        );
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            Ok(1)
+        );
+        assert_eq!(cell.load().workers, 2);
+
+        // A poll that catches a half-written file reports it, the finished file is still reloaded
+        replace_file(&path, "workers=3\ngree", None);
+        assert!(receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .is_err());
+        replace_file(&path, "workers=3\ngreeting=hey", None);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            Ok(2)
+        );
+        assert_eq!(cell.load().workers, 3);
+
+        drop(reloader);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+use be_rust_master::multi_thread_processor::streaming::*;
+use std::io::{self, Cursor};
+
+fn small_config(chunk_size: usize, num_workers: usize) -> StreamConfig {
+    StreamConfig {
+        chunk_size,
+        num_workers,
+        queue_capacity: 2,
+    }
+}
+
+fn to_le_bytes(values: impl IntoIterator<Item = u64>) -> Vec<u8> {
+    values.into_iter().flat_map(u64::to_le_bytes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_sum_of_squares_from_iterator() {
+        let n = 100_000u64;
+        let expected = n * (n + 1) * (2 * n + 1) / 6;
+        for (chunk_size, workers) in [(1, 1), (7, 3), (4096, 8), (1_000_000, 2)] {
+            let config = small_config(chunk_size, workers);
+            assert_eq!(stream_sum_of_squares(1..=n, &config), expected);
+        }
+        assert_eq!(
+            stream_sum_of_squares(std::iter::empty(), &small_config(8, 4)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_custom_reduction_combines_worker_results() {
+        let config = small_config(100, 4);
+        let (count, max) = stream_reduce(
+            (0..10_000u32).map(|x| x * 37 % 10_007),
+            &config,
+            || (0usize, 0u32),
+            |(count, max), chunk| (count + chunk.len(), max.max(*chunk.iter().max().unwrap())),
+            |a, b| (a.0 + b.0, a.1.max(b.1)),
+        );
+        assert_eq!(count, 10_000);
+        assert_eq!(max, (0..10_000u32).map(|x| x * 37 % 10_007).max().unwrap());
+    }
+
+    #[test]
+    fn test_source_error_stops_the_stream() {
+        let source = (0..1000u64).map(|x| if x == 500 { Err("bad record") } else { Ok(x) });
+        let result = try_stream_reduce(
+            source,
+            &small_config(16, 3),
+            || 0,
+            |a, c| a + c.len(),
+            |a, b| a + b,
+        );
+        assert_eq!(result, Err("bad record"));
+    }
+
+    #[test]
+    fn test_worker_panic_propagates_instead_of_hanging() {
+        let config = StreamConfig {
+            chunk_size: 4,
+            num_workers: 1,
+            queue_capacity: 1,
+        };
+        // Run on a separate thread so a regression shows up as a timeout rather than a stuck test run
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = std::panic::catch_unwind(|| {
+                stream_reduce(
+                    0..1000u64,
+                    &config,
+                    || 0,
+                    |_, _| panic!("fold failed"),
+                    |a, b| a + b,
+                )
+            });
+            done_tx.send(result.is_err()).unwrap();
+        });
+        let panicked = done_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("pipeline hung after its only worker panicked");
+        assert!(panicked);
+    }
+
+    #[test]
+    fn test_reader_input_is_decoded_little_endian() {
+        let bytes = to_le_bytes(1..=10_000);
+        let config = small_config(333, 4);
+        let sum = stream_reduce_reader(
+            Cursor::new(bytes),
+            &config,
+            || 0u64,
+            |a, c| a + c.iter().sum::<u64>(),
+            |a, b| a + b,
+        )
+        .unwrap();
+        assert_eq!(sum, 10_000 * 10_001 / 2);
+    }
+
+    #[test]
+    fn test_truncated_reader_input_is_an_error() {
+        let mut bytes = to_le_bytes(0..100);
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let err = stream_reduce_reader(
+            Cursor::new(bytes),
+            &small_config(64, 2),
+            || (),
+            |_, _| (),
+            |_, _| (),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("3 trailing bytes at offset 800"));
+    }
+
+    #[test]
+    fn test_stream_file_sum_of_squares() {
+        let path = std::env::temp_dir().join(format!("streaming_test_{}.bin", std::process::id()));
+        std::fs::write(&path, to_le_bytes([1, 2, 3, 4])).unwrap();
+        let result = stream_file_sum_of_squares(&path, &small_config(3, 2));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), 30);
+    }
+}
@@ -0,0 +1,159 @@
+use be_rust_master::multi_thread_processor::{chunk_ranges, sum_of_squares};
+use be_rust_master::shared_memory_concurrency::sync_primitives::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WORKERS: usize = 4;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latch_releases_after_all_workers_finish() {
+        let data: Vec<u64> = (1..=1_000).collect();
+        let partials = Mutex::new(vec![]);
+        let latch = CountdownLatch::new(WORKERS);
+        thread::scope(|scope| {
+            for range in chunk_ranges(data.len(), WORKERS) {
+                let (data, partials, latch) = (&data, &partials, &latch);
+                scope.spawn(move || {
+                    partials.lock().unwrap().push(sum_of_squares(&data[range]));
+                    latch.count_down();
+                });
+            }
+            latch.wait();
+            assert_eq!(latch.count(), 0);
+            let total: u64 = partials.lock().unwrap().iter().sum();
+            assert_eq!(total, sum_of_squares(&data));
+        });
+    }
+
+    #[test]
+    fn test_latch_timeout() {
+        let latch = CountdownLatch::new(2);
+        latch.count_down();
+        let err = latch.wait_timeout(Duration::from_millis(20)).unwrap_err();
+        assert_eq!(err.timeout, Duration::from_millis(20));
+        latch.count_down();
+        latch.count_down();
+        assert_eq!(latch.wait_timeout(Duration::from_millis(20)), Ok(()));
+    }
+
+    #[test]
+    fn test_barrier_leader_combines_each_phase() {
+        // Each phase every worker squares its chunk, the leader records the phase total
+        let data: Vec<u64> = vec![2; 400];
+        let partials = Arc::new(Mutex::new(vec![]));
+        let totals = Arc::new(Mutex::new(vec![]));
+        let barrier = {
+            let (partials, totals) = (Arc::clone(&partials), Arc::clone(&totals));
+            CyclicBarrier::with_action(WORKERS, move |generation| {
+                let sum: u64 = partials.lock().unwrap().drain(..).sum();
+                totals.lock().unwrap().push((generation, sum));
+            })
+        };
+        let leaders = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for range in chunk_ranges(data.len(), WORKERS) {
+                let (data, partials, barrier, leaders) = (&data, &partials, &barrier, &leaders);
+                scope.spawn(move || {
+                    for phase in 0..3u64 {
+                        let scaled: Vec<u64> =
+                            data[range.clone()].iter().map(|x| x + phase).collect();
+                        partials.lock().unwrap().push(sum_of_squares(&scaled));
+                        let result = barrier.wait();
+                        assert_eq!(result.generation, phase);
+                        if result.is_leader {
+                            leaders.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(leaders.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            *totals.lock().unwrap(),
+            vec![(0, 400 * 4), (1, 400 * 9), (2, 400 * 16)]
+        );
+    }
+
+    #[test]
+    fn test_barrier_timeout_withdraws_waiter() {
+        let barrier = CyclicBarrier::new(2);
+        assert!(barrier.wait_timeout(Duration::from_millis(20)).is_err());
+        // The timed-out thread no longer counts, so two fresh arrivals are needed
+        thread::scope(|scope| {
+            let other = scope.spawn(|| barrier.wait());
+            let mine = barrier.wait_timeout(Duration::from_secs(5)).unwrap();
+            let theirs = other.join().unwrap();
+            assert_eq!(mine.generation, 0);
+            assert_eq!(theirs.generation, 0);
+            assert!(mine.is_leader != theirs.is_leader);
+        });
+    }
+
+    #[test]
+    fn test_barrier_panicking_action_releases_waiters() {
+        let barrier = CyclicBarrier::with_action(2, |generation| {
+            if generation == 0 {
+                panic!("barrier action failed");
+            }
+        });
+        let timeout = Duration::from_secs(5);
+        let started = Instant::now();
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| scope.spawn(|| barrier.wait_timeout(timeout)))
+                .collect();
+            let results: Vec<_> = handles.into_iter().map(|h| h.join()).collect();
+            // The leader unwinds out of the action, the other party is woken rather than timing out
+            assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+            let released = results.into_iter().find_map(Result::ok).unwrap().unwrap();
+            assert!(!released.is_leader);
+            assert_eq!(released.generation, 0);
+            assert!(started.elapsed() < timeout);
+        });
+        // The barrier stays usable for the next generation
+        thread::scope(|scope| {
+            let other = scope.spawn(|| barrier.wait());
+            assert_eq!(barrier.wait().generation, 1);
+            assert_eq!(other.join().unwrap().generation, 1);
+        });
+    }
+
+    #[test]
+    fn test_semaphore_limits_concurrent_workers() {
+        let semaphore = Semaphore::new(2);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let _permit = semaphore.acquire();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(5));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(semaphore.available_permits(), 2);
+    }
+
+    #[test]
+    fn test_semaphore_try_and_timeout() {
+        let semaphore = Semaphore::new(1);
+        let permit = semaphore.try_acquire().unwrap();
+        assert!(semaphore.try_acquire().is_none());
+        assert!(semaphore
+            .acquire_timeout(Duration::from_millis(20))
+            .is_err());
+        drop(permit);
+        assert!(semaphore.acquire_timeout(Duration::from_millis(20)).is_ok());
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+use be_rust_master::shared_memory_concurrency::thread_local_accumulator::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_thread_add_and_merge() {
+        let acc = ThreadLocalAccumulator::<i64>::new();
+        assert_eq!(acc.merge(), 0);
+        assert_eq!(acc.thread_count(), 0);
+        acc.add(5);
+        acc.add(-8);
+        assert_eq!(acc.merge(), -3);
+        assert_eq!(acc.thread_count(), 1);
+    }
+
+    #[test]
+    fn test_merge_is_exact_after_writers_finish() {
+        let acc = ThreadLocalAccumulator::<u64>::new();
+        thread::scope(|scope| {
+            for t in 0..8u64 {
+                let acc = &acc;
+                scope.spawn(move || {
+                    for _ in 0..10_000 {
+                        acc.add(t);
+                    }
+                });
+            }
+        });
+        assert_eq!(acc.merge(), 10_000 * (0..8).sum::<u64>());
+        assert_eq!(acc.thread_count(), 8);
+    }
+
+    #[test]
+    fn test_concurrent_merges_are_monotonic_and_bounded() {
+        let acc = ThreadLocalAccumulator::<usize>::new();
+        let done = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let writers: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        for _ in 0..50_000 {
+                            acc.add(1);
+                        }
+                    })
+                })
+                .collect();
+            let readers: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut last = 0;
+                        while !done.load(Ordering::Acquire) {
+                            let merged = acc.merge();
+                            assert!(merged >= last, "merge went backwards");
+                            assert!(merged <= 200_000);
+                            last = merged;
+                        }
+                    })
+                })
+                .collect();
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            for reader in readers {
+                reader.join().unwrap();
+            }
+        });
+        assert_eq!(acc.merge(), 200_000);
+    }
+
+    #[test]
+    fn test_float_accumulation() {
+        let acc = ThreadLocalAccumulator::<f64>::new();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1_000 {
+                        acc.add(0.5);
+                    }
+                });
+            }
+        });
+        assert_eq!(acc.merge(), 2_000.0);
+    }
+
+    #[test]
+    fn test_accumulators_on_one_thread_are_independent() {
+        let first = ThreadLocalAccumulator::<u64>::new();
+        let second = ThreadLocalAccumulator::<u64>::new();
+        first.add(1);
+        second.add(10);
+        drop(first);
+        let third = ThreadLocalAccumulator::<u64>::new();
+        third.add(100);
+        assert_eq!(second.merge(), 10);
+        assert_eq!(third.merge(), 100);
+    }
+
+    #[test]
+    fn test_slot_cache_does_not_grow_with_dropped_accumulators() {
+        // Dropped on the thread that used it: the entry goes right away
+        let acc = ThreadLocalAccumulator::<u64>::new();
+        acc.add(1);
+        let before = cached_slot_count();
+        drop(acc);
+        assert_eq!(cached_slot_count(), before - 1);
+
+        // Used by a long-lived worker but dropped elsewhere: the worker prunes the dead entries
+        let (jobs, receiver) = mpsc::channel::<Arc<ThreadLocalAccumulator<u64>>>();
+        let (done, acks) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            for acc in receiver {
+                acc.add(1);
+                drop(acc);
+                done.send(()).unwrap();
+            }
+            cached_slot_count()
+        });
+        for _ in 0..1_000 {
+            let acc = Arc::new(ThreadLocalAccumulator::new());
+            jobs.send(Arc::clone(&acc)).unwrap();
+            acks.recv().unwrap();
+            assert_eq!(Arc::into_inner(acc).unwrap().merge(), 1);
+        }
+        drop(jobs);
+        assert!(worker.join().unwrap() <= 32);
+    }
+}